@@ -0,0 +1,51 @@
+use std::{borrow::Cow, marker::PhantomData};
+
+use qeap::{error::Error, transform::DynError};
+use qeap_file::FileFormat;
+
+use crate::{CryptError, KeyProvider, seal, unseal};
+
+/// Encrypts the files written by the format `F`, e.g. `EncryptedFormat<Toml<T>, AppKey>`, so any
+/// [`FileFormat`] can be stored encrypted by `qeap_file::FilePersist`.
+///
+/// Formats have no instance to hold keys, so they come from `K::default()`. The format's
+/// extension is authenticated with the data, but unlike [`Encrypted`](crate::Encrypted) the name
+/// of the data isn't, since formats don't know it.
+pub struct EncryptedFormat<F, K>(PhantomData<(F, K)>);
+
+impl<F, K> FileFormat for EncryptedFormat<F, K>
+where
+    F: FileFormat,
+    K: KeyProvider + Default,
+{
+    type Data = F::Data;
+
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        let ext = F::extension();
+        let plaintext = zeroize::Zeroizing::new(F::encode(data)?);
+        Ok(seal(
+            &K::default().keys()?,
+            &plaintext,
+            ext.as_bytes(),
+            &ext,
+        )?)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        let ext = F::extension();
+        let plaintext =
+            unseal(&K::default().keys()?, bytes, ext.as_bytes(), &ext).map_err(|e| match e {
+                CryptError::Tampered(_) => Box::new(Error::integrity(e)) as DynError,
+                _ => Box::new(e),
+            })?;
+        F::decode(&plaintext)
+    }
+
+    fn ext() -> &'static str {
+        "enc"
+    }
+
+    fn extension() -> Cow<'static, str> {
+        Cow::Owned(format!("{}.enc", F::extension()))
+    }
+}
@@ -0,0 +1,188 @@
+use std::path::PathBuf;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chacha20poly1305::aead::Generate;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::CryptError;
+
+/// A 256-bit XChaCha20-Poly1305 key, which is wiped from memory when dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Key(pub(crate) [u8; 32]);
+
+impl Drop for Key {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Key {
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parses a base64 encoded key, as produced by [`Key::to_base64`].
+    pub fn from_base64(encoded: &str) -> Result<Self, CryptError> {
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .map(Zeroizing::new)
+            .map_err(|e| CryptError::Key(format!("invalid base64: {e}")))?;
+
+        Self::try_from(bytes.as_slice())
+    }
+
+    /// Generates a new random key.
+    pub fn generate() -> Result<Self, CryptError> {
+        <[u8; 32]>::try_generate()
+            .map(Self)
+            .map_err(|e| CryptError::Key(format!("failed to generate a key: {e}")))
+    }
+
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.0)
+    }
+}
+
+impl TryFrom<&[u8]> for Key {
+    type Error = CryptError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; 32]>::try_from(bytes).map(Self).map_err(|_| {
+            CryptError::Key(format!("expected a 32 byte key, got {} bytes", bytes.len()))
+        })
+    }
+}
+
+// never print key material
+impl std::fmt::Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+/// The key new data is encrypted with, plus older keys that existing data may still be encrypted with.
+#[derive(Debug, Clone)]
+pub struct Keys {
+    pub current: Key,
+    pub previous: Vec<Key>,
+}
+
+impl Keys {
+    pub fn new(current: Key) -> Self {
+        Self {
+            current,
+            previous: Vec::new(),
+        }
+    }
+
+    /// Adds a key that was rotated out, so data encrypted with it can still be loaded.
+    pub fn with_previous(mut self, key: Key) -> Self {
+        self.previous.push(key);
+        self
+    }
+
+    pub(crate) fn all(&self) -> impl Iterator<Item = &Key> {
+        std::iter::once(&self.current).chain(&self.previous)
+    }
+}
+
+/// Supplies the keys used by [`Encrypted`](crate::Encrypted) and, through `Default`,
+/// by `EncryptedFormat`.
+pub trait KeyProvider {
+    fn keys(&self) -> Result<Keys, CryptError>;
+}
+
+impl KeyProvider for Keys {
+    fn keys(&self) -> Result<Keys, CryptError> {
+        Ok(self.clone())
+    }
+}
+
+impl KeyProvider for Key {
+    fn keys(&self) -> Result<Keys, CryptError> {
+        Ok(Keys::new(self.clone()))
+    }
+}
+
+/// Reads base64 encoded keys from environment variables.
+pub struct EnvKey {
+    var: String,
+    previous: Vec<String>,
+}
+
+impl EnvKey {
+    pub fn new(var: impl Into<String>) -> Self {
+        Self {
+            var: var.into(),
+            previous: Vec::new(),
+        }
+    }
+
+    /// Adds a variable holding a key that was rotated out.
+    pub fn with_previous(mut self, var: impl Into<String>) -> Self {
+        self.previous.push(var.into());
+        self
+    }
+
+    fn read(var: &str) -> Result<Key, CryptError> {
+        let encoded = std::env::var(var)
+            .map(Zeroizing::new)
+            .map_err(|e| CryptError::Key(format!("failed to read ${var}: {e}")))?;
+
+        Key::from_base64(&encoded)
+    }
+}
+
+impl KeyProvider for EnvKey {
+    fn keys(&self) -> Result<Keys, CryptError> {
+        let mut keys = Keys::new(Self::read(&self.var)?);
+        for var in &self.previous {
+            keys = keys.with_previous(Self::read(var)?);
+        }
+
+        Ok(keys)
+    }
+}
+
+/// Reads keys from files holding either the 32 raw key bytes or the key in base64.
+pub struct KeyFile {
+    path: PathBuf,
+    previous: Vec<PathBuf>,
+}
+
+impl KeyFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            previous: Vec::new(),
+        }
+    }
+
+    /// Adds a file holding a key that was rotated out.
+    pub fn with_previous(mut self, path: impl Into<PathBuf>) -> Self {
+        self.previous.push(path.into());
+        self
+    }
+
+    fn read(path: &PathBuf) -> Result<Key, CryptError> {
+        let contents = std::fs::read(path).map(Zeroizing::new).map_err(|e| {
+            CryptError::Key(format!("failed to read key file '{}': {e}", path.display()))
+        })?;
+
+        match std::str::from_utf8(&contents) {
+            Ok(encoded) if contents.len() != 32 => Key::from_base64(encoded),
+            _ => Key::try_from(contents.as_slice()),
+        }
+    }
+}
+
+impl KeyProvider for KeyFile {
+    fn keys(&self) -> Result<Keys, CryptError> {
+        let mut keys = Keys::new(Self::read(&self.path)?);
+        for path in &self.previous {
+            keys = keys.with_previous(Self::read(path)?);
+        }
+
+        Ok(keys)
+    }
+}
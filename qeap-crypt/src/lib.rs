@@ -0,0 +1,193 @@
+use std::marker::PhantomData;
+
+use chacha20poly1305::{
+    KeyInit, XChaCha20Poly1305, XNonce,
+    aead::{Aead, Generate, Payload},
+};
+use qeap::{LoadOrigin, QeapResult, Qeaper, error::Error};
+use serde::{Serialize, de::DeserializeOwned};
+use zeroize::Zeroizing;
+
+#[cfg(feature = "file")]
+mod format;
+mod key;
+
+#[cfg(feature = "file")]
+pub use format::EncryptedFormat;
+pub use key::{EnvKey, Key, KeyFile, KeyProvider, Keys};
+
+const VERSION: u8 = 1;
+const NONCE_LEN: usize = 24;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CryptError {
+    #[error("invalid encryption key: {0}")]
+    Key(String),
+    #[error("'{0}' failed authentication; it was tampered with or encrypted with an unknown key")]
+    Tampered(String),
+    #[error("'{0}' is not data encrypted by qeap-crypt")]
+    Format(String),
+    #[error("failed to encrypt '{0}'")]
+    Encrypt(String),
+    #[error("failed to serialize '{0}': {1}")]
+    Serialize(String, serde_json::Error),
+    #[error("failed to parse decrypted '{0}': {1}")]
+    Parse(String, serde_json::Error),
+}
+
+impl CryptError {
+    /// Reports data that failed authentication as
+    /// [`ErrorType::Integrity`](qeap::error::ErrorType::Integrity) and everything else as a load error.
+    fn into_load_error(self) -> Error {
+        match self {
+            CryptError::Tampered(_) => Error::integrity(self),
+            _ => Error::load(self),
+        }
+    }
+}
+
+/// Encrypts `plaintext` with the current key and a random nonce, authenticating `aad` along with it.
+///
+/// `label` names the data in errors.
+fn seal(keys: &Keys, plaintext: &[u8], aad: &[u8], label: &str) -> Result<Vec<u8>, CryptError> {
+    let nonce = XNonce::try_generate()
+        .map_err(|e| CryptError::Key(format!("failed to generate a nonce: {e}")))?;
+    let ciphertext = XChaCha20Poly1305::new(&keys.current.0.into())
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| CryptError::Encrypt(label.to_owned()))?;
+
+    let mut encrypted = Vec::with_capacity(1 + NONCE_LEN + ciphertext.len());
+    encrypted.push(VERSION);
+    encrypted.extend_from_slice(&nonce);
+    encrypted.extend_from_slice(&ciphertext);
+    Ok(encrypted)
+}
+
+/// Decrypts data written by [`seal`] with any of the keys.
+fn unseal(
+    keys: &Keys,
+    encrypted: &[u8],
+    aad: &[u8],
+    label: &str,
+) -> Result<Zeroizing<Vec<u8>>, CryptError> {
+    let Some((&VERSION, rest)) = encrypted.split_first() else {
+        return Err(CryptError::Format(label.to_owned()));
+    };
+    if rest.len() < NONCE_LEN {
+        return Err(CryptError::Format(label.to_owned()));
+    }
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let nonce = XNonce::try_from(nonce).map_err(|_| CryptError::Format(label.to_owned()))?;
+
+    keys.all()
+        .find_map(|key| {
+            XChaCha20Poly1305::new(&key.0.into())
+                .decrypt(
+                    &nonce,
+                    Payload {
+                        msg: ciphertext,
+                        aad,
+                    },
+                )
+                .ok()
+        })
+        .map(Zeroizing::new)
+        .ok_or_else(|| CryptError::Tampered(label.to_owned()))
+}
+
+/// Encrypts data with XChaCha20-Poly1305 before handing it to a `Qeaper` that stores bytes.
+///
+/// The data is serialized as JSON, then encrypted with the current key of the [`KeyProvider`]
+/// and a random nonce. The name it's stored under is authenticated too, so data can't be
+/// swapped between types. Loading tries the current key first and then any previous keys;
+/// the next save re-encrypts the data with the current key.
+pub struct Encrypted<T, Q, K> {
+    inner: Q,
+    keys: K,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, Q, K> Encrypted<T, Q, K> {
+    pub fn new(inner: Q, keys: K) -> Self {
+        Self {
+            inner,
+            keys,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, Q, K> Encrypted<T, Q, K>
+where
+    K: KeyProvider,
+{
+    fn encrypt(&self, data: &T, name: &str) -> Result<Vec<u8>, CryptError>
+    where
+        T: Serialize,
+    {
+        let plaintext = Zeroizing::new(
+            serde_json::to_vec(data).map_err(|e| CryptError::Serialize(name.to_owned(), e))?,
+        );
+
+        seal(&self.keys.keys()?, &plaintext, name.as_bytes(), name)
+    }
+
+    fn decrypt(&self, encrypted: &[u8], name: &str) -> Result<T, CryptError>
+    where
+        T: DeserializeOwned,
+    {
+        let plaintext = unseal(&self.keys.keys()?, encrypted, name.as_bytes(), name)?;
+
+        serde_json::from_slice(&plaintext).map_err(|e| CryptError::Parse(name.to_owned(), e))
+    }
+}
+
+impl<T, Q, K> Qeaper for Encrypted<T, Q, K>
+where
+    T: Serialize + DeserializeOwned + Default,
+    Q: Qeaper<Output = Vec<u8>>,
+    K: KeyProvider,
+{
+    type Output = T;
+
+    fn init(&self) -> QeapResult<()> {
+        self.inner.init()
+    }
+
+    fn load(&self, name: &str) -> QeapResult<Self::Output> {
+        self.load_with_origin(name).map(|(data, _)| data)
+    }
+
+    fn load_with_origin(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        let (data, origin) = self.load_deferred(name)?;
+        if origin == LoadOrigin::DefaultCreated {
+            self.save(&data, name)?;
+        }
+        Ok((data, origin))
+    }
+
+    fn load_deferred(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        let (encrypted, origin) = self.inner.load_deferred(name)?;
+
+        // the inner storage made up empty bytes, so make up our data instead
+        if origin.is_default() && encrypted.is_empty() {
+            return Ok((T::default(), origin));
+        }
+
+        let data = self
+            .decrypt(&encrypted, name)
+            .map_err(CryptError::into_load_error)?;
+        Ok((data, origin))
+    }
+
+    fn save(&self, data: &Self::Output, name: &str) -> QeapResult<()> {
+        let encrypted = self.encrypt(data, name).map_err(Error::save)?;
+        self.inner.save(&encrypted, name)
+    }
+}
@@ -0,0 +1,146 @@
+use qeap::{LoadOrigin, Qeaper, error::ErrorType};
+use qeap_crypt::{CryptError, Encrypted, EnvKey, Key, KeyFile, Keys};
+use qeap_file::BytesFile;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct AppConfig {
+    api_key: Option<String>,
+}
+
+fn config() -> AppConfig {
+    AppConfig {
+        api_key: Some("sk-123456".to_owned()),
+    }
+}
+
+fn crypt_error(err: &qeap::error::Error) -> &CryptError {
+    err.cause()
+        .downcast_ref::<CryptError>()
+        .expect("a qeap-crypt error")
+}
+
+#[test]
+fn encrypts_at_rest() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = Key::generate().unwrap();
+    let p = Encrypted::new(BytesFile::new(dir.path()), key.clone());
+    p.init().unwrap();
+
+    let (data, origin) = p.load_with_origin("AppConfig").unwrap();
+    assert_eq!(data, AppConfig::default());
+    assert_eq!(origin, LoadOrigin::DefaultCreated);
+
+    p.save(&config(), "AppConfig").unwrap();
+
+    let stored = std::fs::read(dir.path().join("appconfig.dat")).unwrap();
+    assert!(!stored.windows(9).any(|w| w == b"sk-123456"));
+    assert_eq!(p.load("AppConfig").unwrap(), config());
+}
+
+#[test]
+fn detects_tampering() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = Encrypted::new(BytesFile::new(dir.path()), Key::generate().unwrap());
+    p.save(&config(), "AppConfig").unwrap();
+
+    let path = dir.path().join("appconfig.dat");
+    let mut stored = std::fs::read(&path).unwrap();
+    *stored.last_mut().unwrap() ^= 1;
+    std::fs::write(&path, stored).unwrap();
+
+    let err = p.load("AppConfig").unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Integrity);
+    assert!(matches!(crypt_error(&err), CryptError::Tampered(_)));
+
+    // a different key can't read it either
+    let other =
+        Encrypted::<AppConfig, _, _>::new(BytesFile::new(dir.path()), Key::generate().unwrap());
+    let err = other.load("AppConfig").unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Integrity);
+    assert!(matches!(crypt_error(&err), CryptError::Tampered(_)));
+}
+
+#[test]
+fn rotates_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = Key::generate().unwrap();
+    let new = Key::generate().unwrap();
+
+    Encrypted::new(BytesFile::new(dir.path()), old.clone())
+        .save(&config(), "AppConfig")
+        .unwrap();
+
+    let rotated = Encrypted::<AppConfig, _, _>::new(
+        BytesFile::new(dir.path()),
+        Keys::new(new.clone()).with_previous(old.clone()),
+    );
+    let data = rotated.load("AppConfig").unwrap();
+    assert_eq!(data, config());
+    rotated.save(&data, "AppConfig").unwrap();
+
+    // after saving, only the new key is needed
+    let p = Encrypted::<AppConfig, _, _>::new(BytesFile::new(dir.path()), new);
+    assert_eq!(p.load("AppConfig").unwrap(), config());
+    let p = Encrypted::<AppConfig, _, _>::new(BytesFile::new(dir.path()), old);
+    assert!(p.load("AppConfig").is_err());
+}
+
+#[test]
+fn key_providers() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = Key::generate().unwrap();
+
+    let key_file = dir.path().join("app.key");
+    std::fs::write(&key_file, key.to_base64()).unwrap();
+    let p = Encrypted::new(BytesFile::new(dir.path()), KeyFile::new(&key_file));
+    p.save(&config(), "AppConfig").unwrap();
+
+    // SAFETY: no other test reads or writes this variable
+    unsafe { std::env::set_var("QEAP_CRYPT_TEST_KEY", key.to_base64()) };
+    let p = Encrypted::<AppConfig, _, _>::new(
+        BytesFile::new(dir.path()),
+        EnvKey::new("QEAP_CRYPT_TEST_KEY"),
+    );
+    assert_eq!(p.load("AppConfig").unwrap(), config());
+
+    let p = Encrypted::<AppConfig, _, _>::new(
+        BytesFile::new(dir.path()),
+        EnvKey::new("QEAP_CRYPT_TEST_MISSING_KEY"),
+    );
+    let err = p.load("AppConfig").unwrap_err();
+    assert!(matches!(crypt_error(&err), CryptError::Key(_)));
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn encrypted_format() {
+    use std::sync::LazyLock;
+
+    use qeap_crypt::{EncryptedFormat, KeyProvider};
+    use qeap_file::{FilePersist, toml::Toml};
+
+    static KEY: LazyLock<Key> = LazyLock::new(|| Key::generate().unwrap());
+
+    #[derive(Default)]
+    struct TestKey;
+
+    impl KeyProvider for TestKey {
+        fn keys(&self) -> Result<Keys, CryptError> {
+            Ok(Keys::new(KEY.clone()))
+        }
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let p = FilePersist::<EncryptedFormat<Toml<AppConfig>, TestKey>>::new(dir.path());
+    p.save(&config(), "AppConfig").unwrap();
+
+    let path = dir.path().join("appconfig.toml.enc");
+    let mut stored = std::fs::read(&path).unwrap();
+    assert!(!stored.windows(9).any(|w| w == b"sk-123456"));
+    assert_eq!(p.load("AppConfig").unwrap(), config());
+
+    *stored.last_mut().unwrap() ^= 1;
+    std::fs::write(&path, stored).unwrap();
+    assert_eq!(p.load("AppConfig").unwrap_err().ty(), ErrorType::Integrity);
+}
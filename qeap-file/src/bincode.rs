@@ -0,0 +1,30 @@
+use std::marker::PhantomData;
+
+use qeap::transform::DynError;
+use serde::{Deserialize, Serialize};
+
+use crate::file::FileFormat;
+
+pub struct Bincode<T>(PhantomData<T>);
+impl<T> FileFormat for Bincode<T>
+where
+    T: Serialize + for<'a> Deserialize<'a>,
+{
+    type Data = T;
+
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        Ok(bincode::serde::encode_to_vec(
+            data,
+            bincode::config::standard(),
+        )?)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        let (data, _) = bincode::serde::decode_from_slice(bytes, bincode::config::standard())?;
+        Ok(data)
+    }
+
+    fn ext() -> &'static str {
+        "bin"
+    }
+}
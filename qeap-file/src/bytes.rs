@@ -0,0 +1,23 @@
+use std::marker::PhantomData;
+
+use qeap::transform::DynError;
+
+use crate::file::FileFormat;
+
+/// Stores raw bytes as they are, e.g. data that another layer already encoded or encrypted.
+pub struct Bytes(PhantomData<()>);
+impl FileFormat for Bytes {
+    type Data = Vec<u8>;
+
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        Ok(data.clone())
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        Ok(bytes.to_vec())
+    }
+
+    fn ext() -> &'static str {
+        "dat"
+    }
+}
@@ -0,0 +1,28 @@
+use std::marker::PhantomData;
+
+use qeap::transform::DynError;
+use serde::{Deserialize, Serialize};
+
+use crate::file::FileFormat;
+
+pub struct Cbor<T>(PhantomData<T>);
+impl<T> FileFormat for Cbor<T>
+where
+    T: Serialize + for<'a> Deserialize<'a>,
+{
+    type Data = T;
+
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(data, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        Ok(ciborium::from_reader(bytes)?)
+    }
+
+    fn ext() -> &'static str {
+        "cbor"
+    }
+}
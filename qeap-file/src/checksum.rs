@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::file::{FileError, sibling, write_atomic};
+
+const HEADER_MAGIC: &[u8; 8] = b"QEAPSUM1";
+const DIGEST_LEN: usize = 32;
+
+/// How [`FilePersist`](crate::FilePersist) detects files that were corrupted after being saved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    /// Files are loaded without verification.
+    #[default]
+    None,
+    /// Writes the SHA-256 of a file to a `.sha256` file next to it. Files without one, e.g. those
+    /// saved before checksums were enabled, are loaded without verification.
+    ///
+    /// The sidecar is written before the file and lists both the new and the previous checksum
+    /// until the file is written, so a crash in between doesn't make either version fail to load.
+    Sidecar,
+    /// Puts the SHA-256 in front of the file's contents. Meant for binary formats, as the file
+    /// can't be edited by hand anymore.
+    Header,
+}
+
+impl Checksum {
+    pub(crate) fn sidecar_path(path: &Path) -> std::path::PathBuf {
+        sibling(path, "sha256")
+    }
+
+    /// Reads the file at `path` and whether it matches its checksum. Files that don't match a
+    /// [`Checksum::Header`] fail right away, as they can't have been edited by hand.
+    pub(crate) fn read(self, path: &Path) -> Result<(Vec<u8>, bool), FileError> {
+        let bytes = std::fs::read(path).map_err(|e| FileError::open(path, e))?;
+
+        match self {
+            Checksum::None => Ok((bytes, true)),
+            Checksum::Sidecar => {
+                let Some(expected) = Self::read_sidecar(path)? else {
+                    return Ok((bytes, true));
+                };
+
+                let digest = to_hex(&Sha256::digest(&bytes));
+                let verified = expected.lines().any(|line| line.trim() == digest);
+                Ok((bytes, verified))
+            }
+            Checksum::Header => {
+                let Some((digest, data)) = bytes
+                    .strip_prefix(HEADER_MAGIC)
+                    .and_then(|rest| rest.split_at_checked(DIGEST_LEN))
+                else {
+                    return Err(FileError::integrity(path));
+                };
+
+                if digest != Sha256::digest(data).as_slice() {
+                    return Err(FileError::integrity(path));
+                }
+                Ok((data.to_vec(), true))
+            }
+        }
+    }
+
+    fn read_sidecar(path: &Path) -> Result<Option<String>, FileError> {
+        let sidecar = Self::sidecar_path(path);
+        match std::fs::read_to_string(&sidecar) {
+            Ok(expected) => Ok(Some(expected)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(FileError::open(&sidecar, e)),
+        }
+    }
+
+    /// Writes `bytes` to `path` along with their checksum, giving the files `mode` if set.
+    pub(crate) fn write(
+        self,
+        path: &Path,
+        bytes: Vec<u8>,
+        mode: Option<u32>,
+    ) -> Result<(), FileError> {
+        match self {
+            Checksum::None => write_atomic(path, &bytes, mode),
+            Checksum::Sidecar => {
+                let sidecar = Self::sidecar_path(path);
+                let digest = to_hex(&Sha256::digest(&bytes));
+
+                // the checksum the file on disk is loaded with until it's replaced; files saved
+                // before checksums were enabled load unverified, so theirs is accepted as well
+                let previous = match Self::read_sidecar(path)? {
+                    Some(expected) => expected.lines().next().map(str::to_owned),
+                    None => match std::fs::read(path) {
+                        Ok(existing) => Some(to_hex(&Sha256::digest(&existing))),
+                        Err(_) => None,
+                    },
+                };
+
+                if let Some(previous) = previous.filter(|previous| *previous != digest) {
+                    let both = format!("{digest}\n{previous}\n");
+                    write_atomic(&sidecar, both.as_bytes(), mode)?;
+                }
+                write_atomic(path, &bytes, mode)?;
+                write_atomic(&sidecar, format!("{digest}\n").as_bytes(), mode)
+            }
+            Checksum::Header => {
+                let mut sealed = Vec::with_capacity(HEADER_MAGIC.len() + DIGEST_LEN + bytes.len());
+                sealed.extend_from_slice(HEADER_MAGIC);
+                sealed.extend_from_slice(&Sha256::digest(&bytes));
+                sealed.extend_from_slice(&bytes);
+                write_atomic(path, &sealed, mode)
+            }
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
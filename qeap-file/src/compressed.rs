@@ -0,0 +1,79 @@
+use std::{borrow::Cow, io, marker::PhantomData};
+
+use qeap::transform::DynError;
+
+use crate::file::FileFormat;
+
+/// A compression algorithm that can be layered over a [`FileFormat`] with [`Compressed`].
+pub trait Compression {
+    /// Appended to the wrapped format's extension, e.g. `json.zst`.
+    const EXT: &'static str;
+    fn compress(data: &[u8]) -> io::Result<Vec<u8>>;
+    fn decompress(data: &[u8]) -> io::Result<Vec<u8>>;
+}
+
+#[cfg(feature = "zstd")]
+pub struct Zstd;
+
+#[cfg(feature = "zstd")]
+impl Compression for Zstd {
+    const EXT: &'static str = "zst";
+
+    fn compress(data: &[u8]) -> io::Result<Vec<u8>> {
+        zstd::encode_all(data, zstd::DEFAULT_COMPRESSION_LEVEL)
+    }
+
+    fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+        zstd::decode_all(data)
+    }
+}
+
+#[cfg(feature = "gzip")]
+pub struct Gzip;
+
+#[cfg(feature = "gzip")]
+impl Compression for Gzip {
+    const EXT: &'static str = "gz";
+
+    fn compress(data: &[u8]) -> io::Result<Vec<u8>> {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()
+    }
+
+    fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+        use std::io::Read;
+
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(data).read_to_end(&mut decoded)?;
+        Ok(decoded)
+    }
+}
+
+/// Compresses the files written by the format `F` with `C`, e.g. `Compressed<Json<T>, Zstd>`.
+pub struct Compressed<F, C>(PhantomData<(F, C)>);
+impl<F, C> FileFormat for Compressed<F, C>
+where
+    F: FileFormat,
+    C: Compression,
+{
+    type Data = F::Data;
+
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        Ok(C::compress(&F::encode(data)?)?)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        F::decode(&C::decompress(bytes)?)
+    }
+
+    fn ext() -> &'static str {
+        C::EXT
+    }
+
+    fn extension() -> Cow<'static, str> {
+        Cow::Owned(format!("{}.{}", F::extension(), C::EXT))
+    }
+}
@@ -1,35 +1,70 @@
 use std::{
-    io,
+    borrow::Cow,
+    ffi::OsString,
+    io::{self, Write},
     marker::PhantomData,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
 };
 
-use serde::{Deserialize, Serialize};
+use qeap::{
+    LoadOrigin, OnMissing, QeapResult, Qeaper,
+    error::{Error, ErrorType},
+    transform::DynError,
+};
 
-use qeap::{QeapResult, Qeaper, error::Error, transform::DynError};
+use crate::{checksum::Checksum, legacy::PathIo, permissions::Permissions};
 
 #[derive(Debug, thiserror::Error)]
 pub enum FileError {
     #[error("failed to open file '{0}': {1}")]
     Open(String, io::Error),
     #[error("failed to parse '{0}' as {1}: {2}")]
-    Parse(String, &'static str, DynError),
+    Parse(String, Cow<'static, str>, DynError),
+    #[error("failed to serialize '{0}' as {1}: {2}")]
+    Serialize(String, Cow<'static, str>, DynError),
     #[error("failed to write to file '{0}: {1}")]
     Write(String, io::Error),
+    #[error("file '{0}' does not exist")]
+    NotFound(String),
+    #[error("file '{0}' does not match its checksum")]
+    Integrity(String),
+    /// The file doesn't match its checksum but still parses, so it was most likely edited by hand
+    /// rather than corrupted.
+    #[error("file '{0}' was changed since it was saved and does not match its checksum")]
+    Modified(String),
+    #[error("'{0}' has mode {1:o}, which gives group or others more access than {2:o}")]
+    Insecure(String, u32, u32),
 }
 
 impl FileError {
-    pub fn parse<E>(path: &Path, format: &'static str, cause: E) -> Self
+    pub fn parse<E>(path: &Path, format: impl Into<Cow<'static, str>>, cause: E) -> Self
     where
-        E: std::error::Error + 'static,
+        E: std::error::Error + Send + Sync + 'static,
     {
         Self::Parse(
             path.display().to_string(),
-            format,
+            format.into(),
             Box::new(cause) as DynError,
         )
     }
 
+    pub(crate) fn encode(
+        path: &Path,
+        format: impl Into<Cow<'static, str>>,
+        cause: DynError,
+    ) -> Self {
+        Self::Serialize(path.display().to_string(), format.into(), cause)
+    }
+
+    pub(crate) fn decode(
+        path: &Path,
+        format: impl Into<Cow<'static, str>>,
+        cause: DynError,
+    ) -> Self {
+        Self::Parse(path.display().to_string(), format.into(), cause)
+    }
+
     pub fn open(path: &Path, cause: io::Error) -> Self {
         Self::Open(path.display().to_string(), cause)
     }
@@ -37,65 +72,435 @@ impl FileError {
     pub fn write(path: &Path, cause: io::Error) -> Self {
         Self::Write(path.display().to_string(), cause)
     }
+
+    pub fn not_found(path: &Path) -> Self {
+        Self::NotFound(path.display().to_string())
+    }
+
+    pub fn integrity(path: &Path) -> Self {
+        Self::Integrity(path.display().to_string())
+    }
+
+    pub fn modified(path: &Path) -> Self {
+        Self::Modified(path.display().to_string())
+    }
+
+    pub fn insecure(path: &Path, mode: u32, expected: u32) -> Self {
+        Self::Insecure(path.display().to_string(), mode, expected)
+    }
+
+    /// Reports integrity failures as [`ErrorType::Integrity`](qeap::error::ErrorType::Integrity)
+    /// and everything else as a load error.
+    fn into_load_error(self) -> Error {
+        match &self {
+            FileError::Integrity(_) | FileError::Modified(_) => Error::integrity(self),
+            FileError::Parse(_, _, cause)
+                if cause
+                    .downcast_ref::<Error>()
+                    .is_some_and(|e| e.ty() == ErrorType::Integrity) =>
+            {
+                Error::integrity(self)
+            }
+            _ => Error::load(self),
+        }
+    }
 }
 
+/// Turns data into the bytes of a file and back. Reading and writing the file is left to
+/// [`FilePersist`], so formats can be layered (e.g. compressed) without touching the disk.
+///
+/// Formats that read and write their own files can implement [`PathFormat`](crate::PathFormat)
+/// instead, and be used through [`Legacy`](crate::Legacy).
 pub trait FileFormat {
-    type Data: Serialize + for<'a> Deserialize<'a>;
-    fn serialize_to(data: &Self::Data, path: &Path) -> Result<(), FileError>;
-    fn deserialize_from(path: &Path) -> Result<Self::Data, FileError>;
+    type Data;
+
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError>;
+
+    /// Failures that mean the data was tampered with or corrupted, rather than just not
+    /// parsing, can be returned as a [`qeap::error::Error`] with
+    /// [`ErrorType::Integrity`](qeap::error::ErrorType::Integrity), to be reported as such.
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError>;
+
+    /// The format's own extension, e.g. `json`.
     fn ext() -> &'static str;
+
+    /// The full extension of the format's files, which also names the format in error messages.
+    /// Formats that wrap others add to their extension, e.g. `json.zst`. Defaults to
+    /// [`ext`](Self::ext).
+    fn extension() -> Cow<'static, str> {
+        Cow::Borrowed(Self::ext())
+    }
+
+    /// How [`Legacy`](crate::Legacy) formats are read and written, through files instead of
+    /// bytes.
+    #[doc(hidden)]
+    fn path_io() -> Option<PathIo<Self::Data>> {
+        None
+    }
 }
 
-pub struct FilePersist<F> {
+/// Writes to a temporary file next to `path` and renames it over `path`, so readers and
+/// crashes only ever see the old or the new contents.
+///
+/// If `path` is a symlink, the file it points to is replaced instead. On Unix the file gets
+/// `mode` when given, and otherwise keeps the mode of the file it replaces, as well as its
+/// owner where the process is allowed to set it.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8], mode: Option<u32>) -> Result<(), FileError> {
+    let path = resolve_symlinks(path);
+    let (tmp, mut file) = TempFile::create(&path)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let existing = std::fs::metadata(&path).ok();
+        if let Some(existing) = &existing {
+            // only privileged processes can give files away, so this is best effort
+            let _ = std::os::unix::fs::fchown(&file, Some(existing.uid()), Some(existing.gid()));
+        }
+
+        // set before writing, so the data is never readable with the default mode
+        let mode = mode.or(existing.map(|existing| existing.mode() & 0o7777));
+        if let Some(mode) = mode {
+            file.set_permissions(std::fs::Permissions::from_mode(mode))
+                .map_err(|e| FileError::write(&tmp.0, e))?;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+
+    file.write_all(bytes)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| FileError::write(&tmp.0, e))?;
+
+    std::fs::rename(&tmp.0, &path).map_err(|e| FileError::write(&path, e))
+}
+
+/// Creates `dir` and its missing parents, with the directory mode of `permissions` if given.
+pub(crate) fn create_dir(dir: &Path, permissions: Option<Permissions>) -> Result<(), FileError> {
+    match permissions {
+        Some(permissions) => permissions.create_dir(dir),
+        None => std::fs::create_dir_all(dir).map_err(|e| FileError::write(dir, e)),
+    }
+}
+
+/// The file `path` points to after following symlinks, which doesn't have to exist yet.
+fn resolve_symlinks(path: &Path) -> PathBuf {
+    let mut path = path.to_owned();
+    // bounded, so a symlink loop just ends the search
+    for _ in 0..40 {
+        let Ok(target) = std::fs::read_link(&path) else {
+            break;
+        };
+        path = match path.parent() {
+            Some(dir) => dir.join(target),
+            None => target,
+        };
+    }
+    path
+}
+
+/// A file next to another one, removed again when dropped.
+pub(crate) struct TempFile(pub(crate) PathBuf);
+
+impl TempFile {
+    /// Creates an empty file next to `path` whose name is unique per process and call, e.g.
+    /// `config.toml.1234-0.tmp`, so concurrent writers never share one.
+    ///
+    /// Only the owner can access the file, and creating it fails instead of following whatever
+    /// was already put in its place.
+    pub(crate) fn create(path: &Path) -> Result<(Self, std::fs::File), FileError> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+        let tmp = sibling(path, &format!("{}-{id}.tmp", std::process::id()));
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let file = options.open(&tmp).map_err(|e| FileError::write(&tmp, e))?;
+        Ok((Self(tmp), file))
+    }
+}
+
+/// `path` with `ext` appended to its full file name, e.g. `config.toml.tmp`.
+pub(crate) fn sibling(path: &Path, ext: &str) -> PathBuf {
+    let mut sibling = OsString::from(path.as_os_str());
+    sibling.push(".");
+    sibling.push(ext);
+    PathBuf::from(sibling)
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Persists data in files of the format `F`, one per persistence name, in a root dir.
+///
+/// The struct itself requires `F: FileFormat`, since it keeps the format's default and fallback
+/// functions, so generic code that names a `FilePersist<F>` has to bound `F` as well.
+pub struct FilePersist<F: FileFormat> {
     root_dir: PathBuf,
+    on_missing: OnMissing,
+    default: Option<fn() -> F::Data>,
+    fallbacks: Vec<Format<F::Data>>,
+    migrate: bool,
+    checksum: Checksum,
+    permissions: Option<Permissions>,
     _phantom: PhantomData<F>,
 }
 
+/// The functions of a [`FileFormat`], so formats other than `F` can be kept in a `FilePersist<F>`.
+struct Format<D> {
+    /// The full extension, which also names the format in error messages.
+    ext: Cow<'static, str>,
+    encode: fn(&D) -> Result<Vec<u8>, DynError>,
+    decode: fn(&[u8]) -> Result<D, DynError>,
+    path_io: Option<PathIo<D>>,
+}
+
+impl<D> Format<D> {
+    fn of<F: FileFormat<Data = D>>() -> Self {
+        Self {
+            ext: F::extension(),
+            encode: F::encode,
+            decode: F::decode,
+            path_io: F::path_io(),
+        }
+    }
+
+    /// Decodes the contents of the file at `path`. Path based formats read them from a
+    /// scratch file next to it, since the file itself may start with a checksum.
+    fn decode(&self, bytes: &[u8], path: &Path) -> Result<D, FileError> {
+        match &self.path_io {
+            Some(path_io) => {
+                let (scratch, mut file) = TempFile::create(path)?;
+                file.write_all(bytes)
+                    .map_err(|e| FileError::write(&scratch.0, e))?;
+                (path_io.deserialize_from)(&scratch.0)
+            }
+            None => (self.decode)(bytes).map_err(|e| FileError::decode(path, self.ext.clone(), e)),
+        }
+    }
+
+    /// Encodes the contents of the file at `path`. Path based formats write them to a scratch
+    /// file next to it, so they're written atomically and with a checksum like any other.
+    fn encode(&self, data: &D, path: &Path) -> Result<Vec<u8>, FileError> {
+        match &self.path_io {
+            Some(path_io) => {
+                let (scratch, _) = TempFile::create(path)?;
+                (path_io.serialize_to)(data, &scratch.0)?;
+                std::fs::read(&scratch.0).map_err(|e| FileError::open(&scratch.0, e))
+            }
+            None => (self.encode)(data).map_err(|e| FileError::encode(path, self.ext.clone(), e)),
+        }
+    }
+}
+
 impl<F> FilePersist<F>
 where
     F: FileFormat,
+    F::Data: Default,
 {
     pub fn new(root_dir: impl Into<PathBuf>) -> Self {
         Self {
             root_dir: root_dir.into(),
+            on_missing: OnMissing::default(),
+            default: Some(F::Data::default),
+            fallbacks: Vec::new(),
+            migrate: false,
+            checksum: Checksum::None,
+            permissions: None,
             _phantom: PhantomData,
         }
     }
 
+    pub fn on_missing(mut self, on_missing: OnMissing) -> Self {
+        self.on_missing = on_missing;
+        self
+    }
+}
+
+impl<F> FilePersist<F>
+where
+    F: FileFormat,
+{
+    /// Creates a `FilePersist` for data without a default, which fails to load missing files.
+    pub fn without_default(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+            on_missing: OnMissing::Error,
+            default: None,
+            fallbacks: Vec::new(),
+            migrate: false,
+            checksum: Checksum::None,
+            permissions: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Also loads files in the format `G` when there is no file in the format `F`.
+    ///
+    /// Fallbacks are tried in the order they were added. Data loaded from a fallback is saved
+    /// back in the same format, unless [`migrate`](Self::migrate) is enabled.
+    pub fn fallback<G>(mut self) -> Self
+    where
+        G: FileFormat<Data = F::Data>,
+    {
+        self.fallbacks.push(Format::of::<G>());
+        self
+    }
+
+    /// Saves data in the format `F` even when it was loaded from a fallback format, and removes
+    /// the files in the fallback formats afterwards.
+    pub fn migrate(mut self, migrate: bool) -> Self {
+        self.migrate = migrate;
+        self
+    }
+
+    /// Writes a checksum when saving and verifies it when loading. Files that don't match
+    /// fail to load with [`FileError::Integrity`], or with [`FileError::Modified`] when a file
+    /// with a [`Checksum::Sidecar`] still parses.
+    pub fn checksum(mut self, checksum: Checksum) -> Self {
+        self.checksum = checksum;
+        self
+    }
+
+    /// Creates the root dir and saves files with the given Unix modes, and checks the modes of
+    /// files and the root dir when loading them.
+    pub fn permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = Some(permissions);
+        self
+    }
+
     pub fn root_dir(&self) -> &Path {
         &self.root_dir
     }
 
-    fn file_path(&self, name: &str) -> PathBuf {
+    fn file_path(&self, name: &str, ext: &str) -> PathBuf {
         let mut file_path = self.root_dir.join(name.to_lowercase());
-        file_path.set_extension(F::ext());
+        file_path.set_extension(ext);
         file_path
     }
+
+    /// The first fallback format that has a file for `name`.
+    fn existing_fallback(&self, name: &str) -> Option<(&Format<F::Data>, PathBuf)> {
+        self.fallbacks
+            .iter()
+            .map(|format| (format, self.file_path(name, &format.ext)))
+            .find(|(_, path)| path.exists())
+    }
+
+    fn read_file(&self, format: &Format<F::Data>, path: &Path) -> Result<F::Data, FileError> {
+        if let Some(permissions) = self.permissions {
+            permissions.check(path)?;
+        }
+
+        let (bytes, verified) = self.checksum.read(path)?;
+        match format.decode(&bytes, path) {
+            Ok(data) if verified => Ok(data),
+            Ok(_) => Err(FileError::modified(path)),
+            Err(_) if !verified => Err(FileError::integrity(path)),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn write_file(
+        &self,
+        format: &Format<F::Data>,
+        data: &F::Data,
+        path: &Path,
+    ) -> Result<(), FileError> {
+        let bytes = format.encode(data, path)?;
+        self.checksum
+            .write(path, bytes, self.permissions.map(Permissions::file_mode))
+    }
+
+    fn write(&self, data: &F::Data, name: &str) -> Result<(), FileError> {
+        let file_path = self.file_path(name, &F::extension());
+        if !file_path.exists()
+            && !self.migrate
+            && let Some((format, path)) = self.existing_fallback(name)
+        {
+            return self.write_file(format, data, &path);
+        }
+
+        self.write_file(&Format::of::<F>(), data, &file_path)?;
+
+        if self.migrate {
+            for format in &self.fallbacks {
+                let path = self.file_path(name, &format.ext);
+                for path in [Checksum::sidecar_path(&path), path] {
+                    match std::fs::remove_file(&path) {
+                        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                            return Err(FileError::write(&path, e));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
-impl<T, F> Qeaper for FilePersist<F>
+impl<F> Qeaper for FilePersist<F>
 where
-    F: FileFormat<Data = T>,
-    T: Default,
+    F: FileFormat,
 {
-    type Output = T;
+    type Output = F::Data;
 
     fn load(&self, name: &str) -> QeapResult<Self::Output> {
-        let file_path = self.file_path(name);
-        if !file_path.exists() {
-            let data = T::default();
+        self.load_with_origin(name).map(|(data, _)| data)
+    }
+
+    fn load_with_origin(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        let (data, origin) = self.load_deferred(name)?;
+        if origin == LoadOrigin::DefaultCreated {
             self.save(&data, name)?;
-            Ok(data)
-        } else {
-            F::deserialize_from(&file_path).map_err(Error::load)
+        }
+        Ok((data, origin))
+    }
+
+    fn load_deferred(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        let file_path = self.file_path(name, &F::extension());
+        if file_path.exists() {
+            let data = self
+                .read_file(&Format::of::<F>(), &file_path)
+                .map_err(FileError::into_load_error)?;
+            return Ok((data, LoadOrigin::Storage));
+        }
+
+        if let Some((format, path)) = self.existing_fallback(name) {
+            let data = self
+                .read_file(format, &path)
+                .map_err(FileError::into_load_error)?;
+            return Ok((data, LoadOrigin::Storage));
+        }
+
+        match (self.on_missing, self.default) {
+            (OnMissing::CreateDefault, Some(default)) => {
+                Ok((default(), LoadOrigin::DefaultCreated))
+            }
+            (OnMissing::UseDefault, Some(default)) => Ok((default(), LoadOrigin::Defaulted)),
+            (OnMissing::Error, _) | (_, None) => {
+                Err(Error::not_found(FileError::not_found(&file_path)))
+            }
         }
     }
 
     fn save(&self, data: &Self::Output, name: &str) -> QeapResult<()> {
-        F::serialize_to(data, &self.file_path(name)).map_err(Error::save)
+        self.write(data, name).map_err(Error::save)
     }
 
     fn init(&self) -> QeapResult<()> {
-        std::fs::create_dir_all(&self.root_dir).map_err(Error::init)
+        create_dir(&self.root_dir, self.permissions).map_err(Error::init)
     }
 }
@@ -1,11 +1,9 @@
-use std::{
-    fs::{File, OpenOptions},
-    marker::PhantomData,
-};
+use std::marker::PhantomData;
 
+use qeap::transform::DynError;
 use serde::{Deserialize, Serialize};
 
-use crate::file::{FileError, FileFormat};
+use crate::file::FileFormat;
 
 pub struct Json<T>(PhantomData<T>);
 impl<T> FileFormat for Json<T>
@@ -14,24 +12,12 @@ where
 {
     type Data = T;
 
-    fn serialize_to(
-        data: &Self::Data,
-        path: &std::path::Path,
-    ) -> Result<(), super::file::FileError> {
-        let file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(path)
-            .map_err(|e| FileError::open(path, e))?;
-
-        serde_json::to_writer(file, data).map_err(|e| FileError::parse(&path, Self::ext(), e))
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        Ok(serde_json::to_vec(data)?)
     }
 
-    fn deserialize_from(path: &std::path::Path) -> Result<Self::Data, super::file::FileError> {
-        let file = File::open(path).map_err(|e| FileError::open(path, e))?;
-
-        serde_json::from_reader(file).map_err(|e| FileError::parse(path, Self::ext(), e))
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        Ok(serde_json::from_slice(bytes)?)
     }
 
     fn ext() -> &'static str {
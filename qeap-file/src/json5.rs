@@ -0,0 +1,26 @@
+use std::marker::PhantomData;
+
+use qeap::transform::DynError;
+use serde::{Deserialize, Serialize};
+
+use crate::file::FileFormat;
+
+pub struct Json5<T>(PhantomData<T>);
+impl<T> FileFormat for Json5<T>
+where
+    T: Serialize + for<'a> Deserialize<'a>,
+{
+    type Data = T;
+
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        Ok(json5::to_string(data)?.into_bytes())
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        Ok(json5::from_str(std::str::from_utf8(bytes)?)?)
+    }
+
+    fn ext() -> &'static str {
+        "json5"
+    }
+}
@@ -0,0 +1,58 @@
+use std::{marker::PhantomData, path::Path};
+
+use qeap::transform::DynError;
+
+use crate::file::{FileError, FileFormat};
+
+/// The path based format API from before [`FileFormat`] worked on bytes.
+///
+/// Formats that still read and write their own files can implement this and be used as
+/// `FilePersist<Legacy<MyFormat>>`.
+pub trait PathFormat {
+    type Data;
+    fn serialize_to(data: &Self::Data, path: &Path) -> Result<(), FileError>;
+    fn deserialize_from(path: &Path) -> Result<Self::Data, FileError>;
+    fn ext() -> &'static str;
+}
+
+/// Adapts a [`PathFormat`] to [`FileFormat`].
+///
+/// [`FilePersist`](crate::FilePersist) lets the format write to and read from scratch files next
+/// to the real one, which only the owner can access, and handles the real file like any other.
+/// As the format needs files, it can't be wrapped by other formats, e.g. to compress it.
+pub struct Legacy<F>(PhantomData<F>);
+impl<F> FileFormat for Legacy<F>
+where
+    F: PathFormat,
+{
+    type Data = F::Data;
+
+    fn encode(_data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        Err(NEEDS_FILES.into())
+    }
+
+    fn decode(_bytes: &[u8]) -> Result<Self::Data, DynError> {
+        Err(NEEDS_FILES.into())
+    }
+
+    fn ext() -> &'static str {
+        F::ext()
+    }
+
+    fn path_io() -> Option<PathIo<Self::Data>> {
+        Some(PathIo {
+            serialize_to: F::serialize_to,
+            deserialize_from: F::deserialize_from,
+        })
+    }
+}
+
+const NEEDS_FILES: &str = "path based formats can only be read and written by FilePersist";
+
+/// The functions of a [`PathFormat`], for [`FilePersist`](crate::FilePersist) to call instead of
+/// encoding and decoding bytes.
+#[doc(hidden)]
+pub struct PathIo<D> {
+    pub(crate) serialize_to: fn(&D, &Path) -> Result<(), FileError>,
+    pub(crate) deserialize_from: fn(&Path) -> Result<D, FileError>,
+}
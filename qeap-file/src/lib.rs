@@ -1,6 +1,16 @@
+mod checksum;
 mod file;
+mod legacy;
+mod permissions;
 
-pub use file::FilePersist;
+pub use checksum::Checksum;
+pub use file::{FileError, FileFormat, FilePersist};
+pub use legacy::{Legacy, PathFormat};
+pub use permissions::{OnInsecure, Permissions};
+pub use qeap::OnMissing;
+
+pub mod bytes;
+pub type BytesFile = FilePersist<bytes::Bytes>;
 
 #[cfg(feature = "json")]
 pub mod json;
@@ -11,3 +21,38 @@ pub type JsonFile<T> = FilePersist<json::Json<T>>;
 pub mod toml;
 #[cfg(feature = "toml")]
 pub type TomlFile<T> = FilePersist<toml::Toml<T>>;
+
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+#[cfg(feature = "msgpack")]
+pub type MessagePackFile<T> = FilePersist<msgpack::MessagePack<T>>;
+
+#[cfg(feature = "cbor")]
+pub mod cbor;
+#[cfg(feature = "cbor")]
+pub type CborFile<T> = FilePersist<cbor::Cbor<T>>;
+
+#[cfg(feature = "bincode")]
+pub mod bincode;
+#[cfg(feature = "bincode")]
+pub type BincodeFile<T> = FilePersist<bincode::Bincode<T>>;
+
+#[cfg(feature = "ron")]
+pub mod ron;
+#[cfg(feature = "ron")]
+pub type RonFile<T> = FilePersist<ron::Ron<T>>;
+
+#[cfg(feature = "json5")]
+pub mod json5;
+#[cfg(feature = "json5")]
+pub type Json5File<T> = FilePersist<json5::Json5<T>>;
+
+#[cfg(any(feature = "zstd", feature = "gzip"))]
+pub mod compressed;
+
+#[cfg(any(feature = "toml", feature = "json"))]
+pub mod sections;
+#[cfg(feature = "toml")]
+pub type TomlSectionFile<T> = sections::SectionFile<sections::Toml, T>;
+#[cfg(feature = "json")]
+pub type JsonSectionFile<T> = sections::SectionFile<sections::Json, T>;
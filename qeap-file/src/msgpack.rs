@@ -0,0 +1,27 @@
+use std::marker::PhantomData;
+
+use qeap::transform::DynError;
+use serde::{Deserialize, Serialize};
+
+use crate::file::FileFormat;
+
+pub struct MessagePack<T>(PhantomData<T>);
+impl<T> FileFormat for MessagePack<T>
+where
+    T: Serialize + for<'a> Deserialize<'a>,
+{
+    type Data = T;
+
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        // named fields keep the data readable when struct fields are reordered
+        Ok(rmp_serde::to_vec_named(data)?)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+
+    fn ext() -> &'static str {
+        "msgpack"
+    }
+}
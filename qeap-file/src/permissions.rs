@@ -0,0 +1,101 @@
+use std::{fs::DirBuilder, path::Path};
+
+use crate::file::FileError;
+
+/// What happens when a loaded file gives more access than its configured mode allows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OnInsecure {
+    /// Load the file anyway and log a warning.
+    #[default]
+    Warn,
+    /// Fail with [`FileError::Insecure`].
+    Error,
+}
+
+/// Unix modes for the directories and files written by a persistence mechanism, e.g. to keep
+/// secrets readable by their owner only.
+///
+/// Directories get their mode when they're created, files whenever they're saved. Existing
+/// directories aren't changed, but like files they're checked against their mode when loading and
+/// initializing. On other platforms the modes are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions {
+    dir_mode: u32,
+    file_mode: u32,
+    on_insecure: OnInsecure,
+}
+
+impl Permissions {
+    pub fn new(dir_mode: u32, file_mode: u32) -> Self {
+        Self {
+            dir_mode,
+            file_mode,
+            on_insecure: OnInsecure::default(),
+        }
+    }
+
+    /// `0700` for directories and `0600` for files.
+    pub fn private() -> Self {
+        Self::new(0o700, 0o600)
+    }
+
+    pub fn on_insecure(mut self, on_insecure: OnInsecure) -> Self {
+        self.on_insecure = on_insecure;
+        self
+    }
+
+    /// Creates `dir` and its missing parents with the directory mode. Directories that already
+    /// exist are left as they are, but checked like loaded files.
+    pub(crate) fn create_dir(self, dir: &Path) -> Result<(), FileError> {
+        let mut builder = DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(self.dir_mode);
+        }
+
+        builder.create(dir).map_err(|e| FileError::write(dir, e))?;
+        self.check_mode(dir, self.dir_mode)
+    }
+
+    pub(crate) fn file_mode(self) -> u32 {
+        self.file_mode
+    }
+
+    /// Checks that group and others have no more access to the file at `path` than the
+    /// configured file mode gives them, nor to its directory than the directory mode does.
+    pub(crate) fn check(self, path: &Path) -> Result<(), FileError> {
+        self.check_mode(path, self.file_mode)?;
+        match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => self.check_mode(dir, self.dir_mode),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_mode(self, path: &Path, allowed: u32) -> Result<(), FileError> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = std::fs::metadata(path)
+                .map_err(|e| FileError::open(path, e))?
+                .permissions()
+                .mode()
+                & 0o777;
+            if mode & !allowed & 0o077 == 0 {
+                return Ok(());
+            }
+
+            let err = FileError::insecure(path, mode, allowed);
+            match self.on_insecure {
+                OnInsecure::Warn => log::warn!("{err}"),
+                OnInsecure::Error => return Err(err),
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = (path, allowed);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use std::marker::PhantomData;
+
+use qeap::transform::DynError;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::file::FileFormat;
+
+pub struct Ron<T>(PhantomData<T>);
+impl<T> FileFormat for Ron<T>
+where
+    T: Serialize + for<'a> Deserialize<'a>,
+{
+    type Data = T;
+
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        Ok(ron::ser::to_string_pretty(data, PrettyConfig::default())?.into_bytes())
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        Ok(ron::de::from_bytes(bytes)?)
+    }
+
+    fn ext() -> &'static str {
+        "ron"
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    collections::BTreeMap,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use qeap::{LoadOrigin, OnMissing, QeapResult, Qeaper, error::Error, transform::DynError};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    file::{FileError, create_dir, write_atomic},
+    permissions::Permissions,
+};
+
+/// A document format whose top level is a map of named sections.
+pub trait Sections {
+    type Document: Default;
+    /// The name of the format, used in error messages.
+    const NAME: &'static str;
+    fn parse(bytes: &[u8]) -> Result<Self::Document, DynError>;
+    fn to_bytes(document: &Self::Document) -> Result<Vec<u8>, DynError>;
+    fn get<T: DeserializeOwned>(
+        document: &Self::Document,
+        section: &str,
+    ) -> Option<Result<T, DynError>>;
+    fn set<T: Serialize>(
+        document: &mut Self::Document,
+        section: &str,
+        data: &T,
+    ) -> Result<(), DynError>;
+}
+
+#[cfg(feature = "toml")]
+pub struct Toml;
+
+/// Keeps the file as a `toml_edit` document, so comments and formatting outside of the saved
+/// section stay as they are.
+#[cfg(feature = "toml")]
+impl Sections for Toml {
+    type Document = toml_edit::DocumentMut;
+    const NAME: &'static str = "toml";
+
+    fn parse(bytes: &[u8]) -> Result<Self::Document, DynError> {
+        Ok(std::str::from_utf8(bytes)?.parse()?)
+    }
+
+    fn to_bytes(document: &Self::Document) -> Result<Vec<u8>, DynError> {
+        Ok(document.to_string().into_bytes())
+    }
+
+    fn get<T: DeserializeOwned>(
+        document: &Self::Document,
+        section: &str,
+    ) -> Option<Result<T, DynError>> {
+        use serde::de::IntoDeserializer;
+
+        let value = match document.get(section)?.clone().into_value() {
+            Ok(value) => value,
+            Err(_) => return Some(Err("the section is not a value".into())),
+        };
+        Some(T::deserialize(value.into_deserializer()).map_err(Into::into))
+    }
+
+    fn set<T: Serialize>(
+        document: &mut Self::Document,
+        section: &str,
+        data: &T,
+    ) -> Result<(), DynError> {
+        use toml_edit::Item;
+
+        let value = data.serialize(toml_edit::ser::ValueSerializer::new())?;
+        let item = match Item::Value(value).into_table() {
+            Ok(mut table) => {
+                // keep the comments above the section and its place in the file
+                if let Some(old) = document.get(section).and_then(Item::as_table) {
+                    *table.decor_mut() = old.decor().clone();
+                    if let Some(position) = old.position() {
+                        table.set_position(position);
+                    }
+                }
+                Item::Table(table)
+            }
+            Err(item) => item,
+        };
+
+        document.insert(section, item);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "json")]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl Sections for Json {
+    type Document = serde_json::Map<String, serde_json::Value>;
+    const NAME: &'static str = "json";
+
+    fn parse(bytes: &[u8]) -> Result<Self::Document, DynError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    fn to_bytes(document: &Self::Document) -> Result<Vec<u8>, DynError> {
+        Ok(serde_json::to_vec_pretty(document)?)
+    }
+
+    fn get<T: DeserializeOwned>(
+        document: &Self::Document,
+        section: &str,
+    ) -> Option<Result<T, DynError>> {
+        let value = document.get(section)?.clone();
+        Some(serde_json::from_value(value).map_err(Into::into))
+    }
+
+    fn set<T: Serialize>(
+        document: &mut Self::Document,
+        section: &str,
+        data: &T,
+    ) -> Result<(), DynError> {
+        document.insert(section.to_owned(), serde_json::to_value(data)?);
+        Ok(())
+    }
+}
+
+/// Stores a type as one section of a file shared with other types, e.g. `[AppConfig]` in
+/// `settings.toml`.
+///
+/// Sections are named after the type, and saving one rewrites the file with all other sections
+/// kept as they are. With [`Toml`] that includes their comments and formatting.
+pub struct SectionFile<S, T> {
+    path: PathBuf,
+    on_missing: OnMissing,
+    default: Option<fn() -> T>,
+    permissions: Option<Permissions>,
+    _phantom: PhantomData<S>,
+}
+
+impl<S, T: Default> SectionFile<S, T> {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            on_missing: OnMissing::default(),
+            default: Some(T::default),
+            permissions: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// What to do when the file or the section doesn't exist.
+    pub fn on_missing(mut self, on_missing: OnMissing) -> Self {
+        self.on_missing = on_missing;
+        self
+    }
+}
+
+impl<S, T> SectionFile<S, T> {
+    /// Creates a `SectionFile` for data without a default, which fails to load missing sections.
+    pub fn without_default(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            on_missing: OnMissing::Error,
+            default: None,
+            permissions: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates the file's directory and saves the file with the given Unix modes, and checks
+    /// the modes of both when loading the file.
+    pub fn permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = Some(permissions);
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl<S, T> SectionFile<S, T>
+where
+    S: Sections,
+{
+    fn read(&self) -> Result<S::Document, FileError> {
+        match std::fs::read(&self.path) {
+            Ok(bytes) => S::parse(&bytes).map_err(|e| FileError::decode(&self.path, S::NAME, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(S::Document::default()),
+            Err(e) => Err(FileError::open(&self.path, e)),
+        }
+    }
+
+    fn section_path(&self, section: &str) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(" [{section}]"));
+        path.into()
+    }
+}
+
+/// The lock that serializes saves to the file at `path`.
+///
+/// Types sharing a file save from different threads when loaded as a parallel bundle, so the
+/// read-modify-write of a save must not interleave with another one to the same file. Paths are
+/// canonicalized, so different spellings of a path share a lock.
+fn save_lock(path: &Path) -> Arc<Mutex<()>> {
+    static SAVE_LOCKS: Mutex<BTreeMap<PathBuf, Arc<Mutex<()>>>> = Mutex::new(BTreeMap::new());
+
+    SAVE_LOCKS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(canonical(path))
+        .or_default()
+        .clone()
+}
+
+/// `path` with symlinks and relative parts resolved as far as it exists.
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path)
+        .or_else(|_| match (path.parent(), path.file_name()) {
+            (Some(dir), Some(name)) => std::fs::canonicalize(dir).map(|dir| dir.join(name)),
+            _ => Err(std::io::ErrorKind::NotFound.into()),
+        })
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_owned())
+}
+
+impl<S, T> Qeaper for SectionFile<S, T>
+where
+    S: Sections,
+    T: Serialize + DeserializeOwned,
+{
+    type Output = T;
+
+    fn load(&self, name: &str) -> QeapResult<Self::Output> {
+        self.load_with_origin(name).map(|(data, _)| data)
+    }
+
+    fn load_with_origin(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        let (data, origin) = self.load_deferred(name)?;
+        if origin == LoadOrigin::DefaultCreated {
+            self.save(&data, name)?;
+        }
+        Ok((data, origin))
+    }
+
+    fn load_deferred(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        if let Some(permissions) = self.permissions
+            && self.path.exists()
+        {
+            permissions.check(&self.path).map_err(Error::load)?;
+        }
+
+        let document = self.read().map_err(Error::load)?;
+        if let Some(data) = S::get(&document, name) {
+            let data = data
+                .map_err(|e| FileError::decode(&self.section_path(name), S::NAME, e))
+                .map_err(Error::load)?;
+            return Ok((data, LoadOrigin::Storage));
+        }
+
+        match (self.on_missing, self.default) {
+            (OnMissing::CreateDefault, Some(default)) => {
+                Ok((default(), LoadOrigin::DefaultCreated))
+            }
+            (OnMissing::UseDefault, Some(default)) => Ok((default(), LoadOrigin::Defaulted)),
+            (OnMissing::Error, _) | (_, None) => Err(Error::not_found(FileError::not_found(
+                &self.section_path(name),
+            ))),
+        }
+    }
+
+    fn save(&self, data: &Self::Output, name: &str) -> QeapResult<()> {
+        let lock = save_lock(&self.path);
+        let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut document = self.read().map_err(Error::save)?;
+        S::set(&mut document, name, data)
+            .map_err(|e| FileError::encode(&self.section_path(name), S::NAME, e))
+            .map_err(Error::save)?;
+
+        let bytes = S::to_bytes(&document)
+            .map_err(|e| FileError::encode(&self.path, S::NAME, e))
+            .map_err(Error::save)?;
+        write_atomic(
+            &self.path,
+            &bytes,
+            self.permissions.map(Permissions::file_mode),
+        )
+        .map_err(Error::save)
+    }
+
+    fn init(&self) -> QeapResult<()> {
+        match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => {
+                create_dir(dir, self.permissions).map_err(Error::init)
+            }
+            _ => Ok(()),
+        }
+    }
+}
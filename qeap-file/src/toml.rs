@@ -1,12 +1,9 @@
-use std::{
-    fs::OpenOptions,
-    io::{BufWriter, Write},
-    marker::PhantomData,
-};
+use std::marker::PhantomData;
 
+use qeap::transform::DynError;
 use serde::{Deserialize, Serialize};
 
-use crate::file::{FileError, FileFormat};
+use crate::file::FileFormat;
 
 pub struct Toml<T>(PhantomData<T>);
 impl<T> FileFormat for Toml<T>
@@ -15,29 +12,12 @@ where
 {
     type Data = T;
 
-    fn serialize_to(
-        data: &Self::Data,
-        path: &std::path::Path,
-    ) -> Result<(), super::file::FileError> {
-        let file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(path)
-            .map_err(|e| FileError::open(path, e))?;
-
-        let as_str = toml::to_string(data).map_err(|e| FileError::parse(path, Self::ext(), e))?;
-        let mut writer = BufWriter::new(file);
-        writer
-            .write_all(as_str.as_bytes())
-            .map_err(|e| FileError::write(&path, e))
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        Ok(toml::to_string(data)?.into_bytes())
     }
 
-    fn deserialize_from(path: &std::path::Path) -> Result<Self::Data, super::file::FileError> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| FileError::Open(path.display().to_string(), e))?;
-
-        toml::from_str(&content).map_err(|e| FileError::parse(path, Self::ext(), e))
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        Ok(toml::from_slice(bytes)?)
     }
 
     fn ext() -> &'static str {
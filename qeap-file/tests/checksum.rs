@@ -0,0 +1,123 @@
+#![cfg(feature = "toml")]
+
+use qeap::{Qeaper, error::ErrorType};
+use qeap_file::{BytesFile, Checksum, FileError, TomlFile};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Config {
+    port: u16,
+}
+
+#[test]
+fn sidecar_checksum_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = TomlFile::<Config>::new(dir.path()).checksum(Checksum::Sidecar);
+
+    p.save(&Config { port: 8080 }, "Config").unwrap();
+    assert!(dir.path().join("config.toml.sha256").exists());
+    assert_eq!(p.load("Config").unwrap(), Config { port: 8080 });
+}
+
+#[test]
+fn sidecar_checksum_tells_edits_from_corruption() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = TomlFile::<Config>::new(dir.path()).checksum(Checksum::Sidecar);
+
+    p.save(&Config { port: 8080 }, "Config").unwrap();
+    std::fs::write(dir.path().join("config.toml"), "port = 9090\n").unwrap();
+
+    let err = p.load("Config").unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Integrity);
+    assert!(matches!(
+        err.cause().downcast_ref::<FileError>(),
+        Some(FileError::Modified(_))
+    ));
+
+    std::fs::write(dir.path().join("config.toml"), "port = \u{0}9090\n").unwrap();
+
+    let err = p.load("Config").unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Integrity);
+    assert!(matches!(
+        err.cause().downcast_ref::<FileError>(),
+        Some(FileError::Integrity(_))
+    ));
+}
+
+#[test]
+fn sidecar_checksum_survives_interrupted_saves() {
+    let old = tempfile::tempdir().unwrap();
+    let new = tempfile::tempdir().unwrap();
+    TomlFile::<Config>::new(old.path())
+        .checksum(Checksum::Sidecar)
+        .save(&Config { port: 8080 }, "Config")
+        .unwrap();
+    TomlFile::<Config>::new(new.path())
+        .checksum(Checksum::Sidecar)
+        .save(&Config { port: 9090 }, "Config")
+        .unwrap();
+
+    let checksum = |dir: &tempfile::TempDir| {
+        std::fs::read_to_string(dir.path().join("config.toml.sha256")).unwrap()
+    };
+    let sidecar = old.path().join("config.toml.sha256");
+    let p = TomlFile::<Config>::new(old.path()).checksum(Checksum::Sidecar);
+
+    // the sidecar was written, but the file wasn't replaced yet
+    std::fs::write(&sidecar, format!("{}{}", checksum(&new), checksum(&old))).unwrap();
+    assert_eq!(p.load("Config").unwrap(), Config { port: 8080 });
+
+    // the file was replaced, but the sidecar wasn't finished
+    std::fs::copy(
+        new.path().join("config.toml"),
+        old.path().join("config.toml"),
+    )
+    .unwrap();
+    assert_eq!(p.load("Config").unwrap(), Config { port: 9090 });
+
+    p.save(&Config { port: 7070 }, "Config").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&sidecar).unwrap().lines().count(),
+        1
+    );
+}
+
+#[test]
+fn files_without_sidecar_load_unverified() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("config.toml"), "port = 9090\n").unwrap();
+
+    let p = TomlFile::<Config>::new(dir.path()).checksum(Checksum::Sidecar);
+    assert_eq!(p.load("Config").unwrap(), Config { port: 9090 });
+}
+
+#[test]
+fn header_checksum_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = BytesFile::new(dir.path()).checksum(Checksum::Header);
+
+    p.save(&vec![1, 2, 3], "Blob").unwrap();
+    assert_ne!(
+        std::fs::read(dir.path().join("blob.dat")).unwrap(),
+        [1, 2, 3]
+    );
+    assert_eq!(p.load("Blob").unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn header_checksum_detects_corruption() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = BytesFile::new(dir.path()).checksum(Checksum::Header);
+
+    p.save(&vec![1, 2, 3], "Blob").unwrap();
+    let path = dir.path().join("blob.dat");
+    let mut bytes = std::fs::read(&path).unwrap();
+    *bytes.last_mut().unwrap() ^= 0xff;
+    std::fs::write(&path, &bytes).unwrap();
+
+    assert_eq!(p.load("Blob").unwrap_err().ty(), ErrorType::Integrity);
+
+    // a truncated file is caught too
+    std::fs::write(&path, &bytes[..10]).unwrap();
+    assert_eq!(p.load("Blob").unwrap_err().ty(), ErrorType::Integrity);
+}
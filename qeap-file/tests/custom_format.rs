@@ -0,0 +1,220 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use qeap::{Qeaper, transform::DynError};
+use qeap_file::{Checksum, FileError, FileFormat, FilePersist, Legacy, PathFormat};
+
+/// Stores a list of lines as plain text.
+struct Lines;
+impl FileFormat for Lines {
+    type Data = Vec<String>;
+
+    fn encode(data: &Self::Data) -> Result<Vec<u8>, DynError> {
+        Ok(data.join("\n").into_bytes())
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self::Data, DynError> {
+        let text = std::str::from_utf8(bytes)?;
+        Ok(text.lines().map(str::to_owned).collect())
+    }
+
+    fn ext() -> &'static str {
+        "txt"
+    }
+}
+
+/// The files the path based format was given, with their Unix modes.
+static PATHS: Mutex<Vec<(PathBuf, u32)>> = Mutex::new(Vec::new());
+
+fn record(path: &Path) {
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+    };
+    #[cfg(not(unix))]
+    let mode = 0;
+    PATHS.lock().unwrap().push((path.to_owned(), mode));
+}
+
+/// The same format, written against the path based API.
+struct PathLines;
+impl PathFormat for PathLines {
+    type Data = Vec<String>;
+
+    fn serialize_to(data: &Self::Data, path: &Path) -> Result<(), FileError> {
+        record(path);
+        std::fs::write(path, data.join("\n")).map_err(|e| FileError::write(path, e))
+    }
+
+    fn deserialize_from(path: &Path) -> Result<Self::Data, FileError> {
+        record(path);
+        let text = std::fs::read_to_string(path).map_err(|e| FileError::open(path, e))?;
+        Ok(text.lines().map(str::to_owned).collect())
+    }
+
+    fn ext() -> &'static str {
+        "txt"
+    }
+}
+
+fn lines() -> Vec<String> {
+    vec!["first".to_owned(), "second".to_owned()]
+}
+
+#[test]
+fn byte_format_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = FilePersist::<Lines>::new(dir.path());
+
+    p.save(&lines(), "Lines").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("lines.txt")).unwrap(),
+        "first\nsecond"
+    );
+    assert_eq!(p.load("Lines").unwrap(), lines());
+}
+
+#[test]
+fn saves_leave_no_temporary_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = FilePersist::<Lines>::new(dir.path());
+
+    p.save(&lines(), "Lines").unwrap();
+    p.save(&lines(), "Lines").unwrap();
+
+    let files: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(files, ["lines.txt"]);
+}
+
+#[test]
+fn concurrent_saves_use_their_own_temporary_files() {
+    let dir = tempfile::tempdir().unwrap();
+
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                let p = FilePersist::<Lines>::new(dir.path());
+                for _ in 0..20 {
+                    p.save(&lines(), "Lines").unwrap();
+                }
+            });
+        }
+    });
+
+    let files: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(files, ["lines.txt"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn saves_replace_the_target_of_symlinks() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("shared/lines.txt");
+    std::fs::create_dir(dir.path().join("shared")).unwrap();
+    std::fs::write(&target, "old").unwrap();
+    std::os::unix::fs::symlink("shared/lines.txt", dir.path().join("lines.txt")).unwrap();
+
+    FilePersist::<Lines>::new(dir.path())
+        .save(&lines(), "Lines")
+        .unwrap();
+
+    let link = std::fs::symlink_metadata(dir.path().join("lines.txt")).unwrap();
+    assert!(link.file_type().is_symlink());
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "first\nsecond");
+}
+
+#[cfg(unix)]
+#[test]
+fn saves_keep_the_mode_of_existing_files() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lines.txt");
+    std::fs::write(&path, "old").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+    FilePersist::<Lines>::new(dir.path())
+        .save(&lines(), "Lines")
+        .unwrap();
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+}
+
+#[test]
+fn decode_errors_name_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("lines.txt"), [0xff, 0xfe]).unwrap();
+
+    let err = FilePersist::<Lines>::new(dir.path())
+        .load("Lines")
+        .unwrap_err();
+    assert!(err.to_string().contains("lines.txt"));
+}
+
+#[test]
+fn path_based_formats_work_through_legacy() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = FilePersist::<Legacy<PathLines>>::new(dir.path());
+
+    p.save(&lines(), "Lines").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("lines.txt")).unwrap(),
+        "first\nsecond"
+    );
+
+    // files written by either API can be read by the other
+    assert_eq!(
+        FilePersist::<Lines>::new(dir.path()).load("Lines").unwrap(),
+        lines()
+    );
+    assert_eq!(p.load("Lines").unwrap(), lines());
+
+    // the format only ever sees private scratch files next to the real one
+    let paths = PATHS.lock().unwrap().clone();
+    assert!(!paths.is_empty());
+    for (path, mode) in paths {
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(
+            name.starts_with("lines.txt.") && name.ends_with(".tmp"),
+            "{name}"
+        );
+        assert_ne!(path.parent(), Some(std::env::temp_dir().as_path()));
+        assert!(!path.exists());
+        if cfg!(unix) {
+            assert_eq!(mode, 0o600);
+        }
+    }
+    let files: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(files, ["lines.txt"]);
+}
+
+#[test]
+fn path_based_formats_get_checksums() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = FilePersist::<Legacy<PathLines>>::new(dir.path()).checksum(Checksum::Header);
+
+    p.save(&lines(), "Lines").unwrap();
+    assert_eq!(p.load("Lines").unwrap(), lines());
+
+    let path = dir.path().join("lines.txt");
+    let mut bytes = std::fs::read(&path).unwrap();
+    *bytes.last_mut().unwrap() ^= 1;
+    std::fs::write(&path, bytes).unwrap();
+    assert_eq!(
+        p.load("Lines").unwrap_err().ty(),
+        qeap::error::ErrorType::Integrity
+    );
+}
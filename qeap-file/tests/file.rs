@@ -0,0 +1,65 @@
+#![cfg(feature = "toml")]
+
+use qeap::{LoadOrigin, Qeaper, error::ErrorType};
+use qeap_file::{OnMissing, TomlFile};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Config {
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Credentials {
+    token: String,
+}
+
+#[test]
+fn missing_file_creates_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = TomlFile::<Config>::new(dir.path());
+
+    let (config, origin) = p.load_with_origin("Config").unwrap();
+    assert_eq!(config, Config::default());
+    assert_eq!(origin, LoadOrigin::DefaultCreated);
+    assert!(dir.path().join("config.toml").exists());
+
+    let (_, origin) = p.load_with_origin("Config").unwrap();
+    assert_eq!(origin, LoadOrigin::Storage);
+}
+
+#[test]
+fn missing_file_uses_default_without_writing() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = TomlFile::<Config>::new(dir.path()).on_missing(OnMissing::UseDefault);
+
+    let (config, origin) = p.load_with_origin("Config").unwrap();
+    assert_eq!(config, Config::default());
+    assert_eq!(origin, LoadOrigin::Defaulted);
+    assert!(!dir.path().join("config.toml").exists());
+}
+
+#[test]
+fn missing_file_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = TomlFile::<Config>::new(dir.path()).on_missing(OnMissing::Error);
+
+    let err = p.load("Config").unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Load);
+    assert!(err.is_not_found());
+    assert!(err.to_string().contains("does not exist"));
+}
+
+#[test]
+fn types_without_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = TomlFile::<Credentials>::without_default(dir.path());
+
+    assert!(p.load("Credentials").is_err());
+
+    let credentials = Credentials {
+        token: "secret".to_owned(),
+    };
+    p.save(&credentials, "Credentials").unwrap();
+    assert_eq!(p.load("Credentials").unwrap(), credentials);
+}
@@ -0,0 +1,184 @@
+#![cfg(any(
+    feature = "json",
+    feature = "toml",
+    feature = "msgpack",
+    feature = "cbor",
+    feature = "bincode",
+    feature = "ron",
+    feature = "json5"
+))]
+
+use std::collections::BTreeMap;
+
+use qeap::Qeaper;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct State {
+    name: String,
+    values: Vec<u32>,
+    lookup: BTreeMap<String, Option<f64>>,
+    mode: Mode,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+enum Mode {
+    #[default]
+    Idle,
+    Running {
+        since: u64,
+    },
+    Paused(u8, bool),
+}
+
+fn sample() -> State {
+    State {
+        name: "cache".to_owned(),
+        values: vec![1, 2, 3],
+        lookup: BTreeMap::from([("a".to_owned(), Some(1.5)), ("b".to_owned(), None)]),
+        mode: Mode::Running {
+            since: 1_700_000_000,
+        },
+    }
+}
+
+#[cfg(any(
+    feature = "json",
+    feature = "msgpack",
+    feature = "cbor",
+    feature = "bincode",
+    feature = "ron",
+    feature = "json5"
+))]
+fn round_trip<P: Qeaper<Output = State>>(p: P, dir: &std::path::Path, file_name: &str) {
+    p.save(&sample(), "State").unwrap();
+    assert!(dir.join(file_name).exists());
+    assert_eq!(p.load("State").unwrap(), sample());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json() {
+    let dir = tempfile::tempdir().unwrap();
+    round_trip(
+        qeap_file::JsonFile::new(dir.path()),
+        dir.path(),
+        "state.json",
+    );
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = qeap_file::TomlFile::new(dir.path());
+    // TOML has no null, so leave out the `None`
+    let mut state = sample();
+    state.lookup.remove("b");
+    p.save(&state, "State").unwrap();
+    assert_eq!(p.load("State").unwrap(), state);
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn msgpack() {
+    let dir = tempfile::tempdir().unwrap();
+    round_trip(
+        qeap_file::MessagePackFile::new(dir.path()),
+        dir.path(),
+        "state.msgpack",
+    );
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn cbor() {
+    let dir = tempfile::tempdir().unwrap();
+    round_trip(
+        qeap_file::CborFile::new(dir.path()),
+        dir.path(),
+        "state.cbor",
+    );
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn bincode() {
+    let dir = tempfile::tempdir().unwrap();
+    round_trip(
+        qeap_file::BincodeFile::new(dir.path()),
+        dir.path(),
+        "state.bin",
+    );
+}
+
+#[cfg(feature = "ron")]
+#[test]
+fn ron() {
+    let dir = tempfile::tempdir().unwrap();
+    round_trip(qeap_file::RonFile::new(dir.path()), dir.path(), "state.ron");
+}
+
+#[cfg(feature = "json5")]
+#[test]
+fn json5() {
+    let dir = tempfile::tempdir().unwrap();
+    round_trip(
+        qeap_file::Json5File::new(dir.path()),
+        dir.path(),
+        "state.json5",
+    );
+
+    // hand-edited files may contain comments and trailing commas
+    std::fs::write(
+        dir.path().join("state.json5"),
+        "{ name: 'edited', // a comment\n values: [4,], lookup: {}, mode: 'Idle', }",
+    )
+    .unwrap();
+    let p = qeap_file::Json5File::<State>::new(dir.path());
+    let state = p.load("State").unwrap();
+    assert_eq!(state.name, "edited");
+    assert_eq!(state.values, [4]);
+}
+
+#[cfg(all(feature = "json", feature = "zstd"))]
+#[test]
+fn zstd() {
+    use qeap_file::{
+        FilePersist,
+        compressed::{Compressed, Zstd},
+        json::Json,
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    round_trip(
+        FilePersist::<Compressed<Json<State>, Zstd>>::new(dir.path()),
+        dir.path(),
+        "state.json.zst",
+    );
+    // only the compressed file is left behind
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[cfg(all(feature = "toml", feature = "gzip"))]
+#[test]
+fn gzip() {
+    use qeap_file::{
+        FilePersist,
+        compressed::{Compressed, Gzip},
+        toml::Toml,
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let p = FilePersist::<Compressed<Toml<State>, Gzip>>::new(dir.path());
+    p.save(&State::default(), "State").unwrap();
+
+    let compressed = std::fs::read(dir.path().join("state.toml.gz")).unwrap();
+    assert_eq!(compressed[..2], [0x1f, 0x8b]);
+    assert_eq!(p.load("State").unwrap(), State::default());
+
+    // errors name the whole format, not just the compression
+    std::fs::write(dir.path().join("state.toml.gz"), b"not gzip").unwrap();
+    let err = p.load("State").unwrap_err();
+    assert!(err.to_string().contains("as toml.gz"), "{err}");
+}
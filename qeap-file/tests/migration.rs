@@ -0,0 +1,72 @@
+#![cfg(all(feature = "json", feature = "toml"))]
+
+use qeap::{LoadOrigin, Qeaper};
+use qeap_file::{FilePersist, json::Json, toml::Toml};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Config {
+    port: u16,
+}
+
+fn persist(dir: &std::path::Path) -> FilePersist<Toml<Config>> {
+    FilePersist::new(dir).fallback::<Json<Config>>()
+}
+
+#[test]
+fn loads_fallback_format() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("config.json"), r#"{"port":8080}"#).unwrap();
+
+    let (config, origin) = persist(dir.path()).load_with_origin("Config").unwrap();
+    assert_eq!(config, Config { port: 8080 });
+    assert_eq!(origin, LoadOrigin::Storage);
+    assert!(!dir.path().join("config.toml").exists());
+}
+
+#[test]
+fn preferred_format_wins() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("config.json"), r#"{"port":8080}"#).unwrap();
+    std::fs::write(dir.path().join("config.toml"), "port = 9090").unwrap();
+
+    let config = persist(dir.path()).load("Config").unwrap();
+    assert_eq!(config, Config { port: 9090 });
+}
+
+#[test]
+fn saves_back_to_fallback_without_migration() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("config.json"), r#"{"port":8080}"#).unwrap();
+
+    let p = persist(dir.path());
+    p.save(&Config { port: 9090 }, "Config").unwrap();
+
+    assert!(!dir.path().join("config.toml").exists());
+    assert_eq!(p.load("Config").unwrap(), Config { port: 9090 });
+}
+
+#[test]
+fn migrates_to_preferred_format() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("config.json"), r#"{"port":8080}"#).unwrap();
+
+    let p = persist(dir.path()).migrate(true);
+    let config = p.load("Config").unwrap();
+    p.save(&config, "Config").unwrap();
+
+    assert!(!dir.path().join("config.json").exists());
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("config.toml")).unwrap(),
+        "port = 8080\n"
+    );
+}
+
+#[test]
+fn missing_files_create_preferred_format() {
+    let dir = tempfile::tempdir().unwrap();
+
+    persist(dir.path()).load("Config").unwrap();
+    assert!(dir.path().join("config.toml").exists());
+    assert!(!dir.path().join("config.json").exists());
+}
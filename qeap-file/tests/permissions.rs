@@ -0,0 +1,113 @@
+#![cfg(all(unix, feature = "toml"))]
+
+use std::{fs, os::unix::fs::PermissionsExt, path::Path};
+
+use qeap::{Qeaper, error::ErrorType};
+use qeap_file::{Checksum, OnInsecure, Permissions, TomlFile, TomlSectionFile};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Secrets {
+    token: String,
+}
+
+fn mode(path: &Path) -> u32 {
+    fs::metadata(path).unwrap().permissions().mode() & 0o777
+}
+
+#[test]
+fn applies_modes_on_creation() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("secrets");
+    let p = TomlFile::<Secrets>::new(&root)
+        .permissions(Permissions::private())
+        .checksum(Checksum::Sidecar);
+
+    p.init().unwrap();
+    p.load("Secrets").unwrap();
+
+    assert_eq!(mode(&root), 0o700);
+    assert_eq!(mode(&root.join("secrets.toml")), 0o600);
+    assert_eq!(mode(&root.join("secrets.toml.sha256")), 0o600);
+}
+
+#[test]
+fn saving_tightens_existing_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("secrets.toml");
+    fs::write(&path, "token = \"abc\"\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+    let p = TomlFile::<Secrets>::new(dir.path()).permissions(Permissions::private());
+    let secrets = p.load("Secrets").unwrap();
+    p.save(&secrets, "Secrets").unwrap();
+
+    assert_eq!(mode(&path), 0o600);
+}
+
+#[test]
+fn readable_files_warn_by_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("secrets.toml");
+    fs::write(&path, "token = \"abc\"\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+    let p = TomlFile::<Secrets>::new(dir.path()).permissions(Permissions::private());
+    assert_eq!(p.load("Secrets").unwrap().token, "abc");
+}
+
+#[test]
+fn readable_files_can_error() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o700)).unwrap();
+    let path = dir.path().join("secrets.toml");
+    fs::write(&path, "token = \"abc\"\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+    let p = TomlFile::<Secrets>::new(dir.path())
+        .permissions(Permissions::private().on_insecure(OnInsecure::Error));
+    let err = p.load("Secrets").unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Load);
+    assert!(err.to_string().contains("640"));
+
+    // stricter modes than configured are fine
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o400)).unwrap();
+    p.load("Secrets").unwrap();
+}
+
+#[test]
+fn section_files_use_modes_too() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config").join("settings.toml");
+    let p = TomlSectionFile::<Secrets>::new(&path).permissions(Permissions::private());
+
+    p.init().unwrap();
+    p.load("Secrets").unwrap();
+
+    assert_eq!(mode(path.parent().unwrap()), 0o700);
+    assert_eq!(mode(&path), 0o600);
+}
+
+#[test]
+fn existing_dirs_are_checked() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("secrets");
+    fs::create_dir(&root).unwrap();
+    fs::set_permissions(&root, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let p = TomlFile::<Secrets>::new(&root)
+        .permissions(Permissions::private().on_insecure(OnInsecure::Error));
+    let err = p.init().unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Init);
+    assert!(err.to_string().contains("755"), "{err}");
+
+    // existing dirs are left as they are, so loading fails too
+    fs::write(root.join("secrets.toml"), "token = \"abc\"\n").unwrap();
+    fs::set_permissions(root.join("secrets.toml"), fs::Permissions::from_mode(0o600)).unwrap();
+    assert_eq!(mode(&root), 0o755);
+    assert!(p.load("Secrets").unwrap_err().to_string().contains("755"));
+
+    fs::set_permissions(&root, fs::Permissions::from_mode(0o700)).unwrap();
+    p.init().unwrap();
+    assert_eq!(p.load("Secrets").unwrap().token, "abc");
+}
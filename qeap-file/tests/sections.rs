@@ -0,0 +1,168 @@
+#![cfg(feature = "toml")]
+
+use qeap::{LoadOrigin, Qeaper, error::ErrorType};
+use qeap_file::{OnMissing, TomlSectionFile};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct AppConfig {
+    port: u16,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct UserPreferences {
+    theme: String,
+}
+
+#[test]
+fn stores_types_as_sections_of_one_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("settings.toml");
+
+    TomlSectionFile::<AppConfig>::new(&path)
+        .save(&AppConfig { port: 8080 }, "AppConfig")
+        .unwrap();
+    TomlSectionFile::<UserPreferences>::new(&path)
+        .save(
+            &UserPreferences {
+                theme: "dark".to_owned(),
+            },
+            "UserPreferences",
+        )
+        .unwrap();
+
+    let settings: toml::Table = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(settings["AppConfig"]["port"].as_integer(), Some(8080));
+    assert_eq!(settings["UserPreferences"]["theme"].as_str(), Some("dark"));
+}
+
+#[test]
+fn saving_keeps_other_sections() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("settings.toml");
+    std::fs::write(
+        &path,
+        "[AppConfig]\nport = 80\n\n[Unrelated]\nkept = true\n",
+    )
+    .unwrap();
+
+    let p = TomlSectionFile::<AppConfig>::new(&path);
+    assert_eq!(p.load("AppConfig").unwrap(), AppConfig { port: 80 });
+    p.save(&AppConfig { port: 8080 }, "AppConfig").unwrap();
+
+    let settings: toml::Table = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(settings["AppConfig"]["port"].as_integer(), Some(8080));
+    assert_eq!(settings["Unrelated"]["kept"].as_bool(), Some(true));
+}
+
+#[test]
+fn saving_keeps_comments_and_formatting() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("settings.toml");
+    let unrelated = "# kept by hand\n[Unrelated]\nkept   = true # really\nlist = [ 1,2 ]\n";
+    std::fs::write(
+        &path,
+        format!("# the server\n[AppConfig]\nport = 80\n\n{unrelated}"),
+    )
+    .unwrap();
+
+    TomlSectionFile::<AppConfig>::new(&path)
+        .save(&AppConfig { port: 8080 }, "AppConfig")
+        .unwrap();
+
+    let settings = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        settings.starts_with("# the server\n[AppConfig]\nport = 8080\n"),
+        "{settings}"
+    );
+    assert!(settings.ends_with(unrelated), "{settings}");
+}
+
+#[test]
+fn missing_section_creates_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("settings.toml");
+    std::fs::write(&path, "[Unrelated]\nkept = true\n").unwrap();
+
+    let (config, origin) = TomlSectionFile::<AppConfig>::new(&path)
+        .load_with_origin("AppConfig")
+        .unwrap();
+    assert_eq!(config, AppConfig::default());
+    assert_eq!(origin, LoadOrigin::DefaultCreated);
+
+    let settings = std::fs::read_to_string(&path).unwrap();
+    assert!(settings.contains("[AppConfig]"));
+    assert!(settings.contains("[Unrelated]"));
+}
+
+#[test]
+fn missing_section_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = TomlSectionFile::<AppConfig>::new(dir.path().join("settings.toml"))
+        .on_missing(OnMissing::Error);
+
+    let err = p.load("AppConfig").unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Load);
+    assert!(err.is_not_found());
+    assert!(err.to_string().contains("[AppConfig]"));
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Session {
+    token: String,
+}
+
+#[test]
+fn without_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = TomlSectionFile::<Session>::without_default(dir.path().join("settings.toml"));
+
+    assert!(p.load("Session").unwrap_err().is_not_found());
+
+    let session = Session {
+        token: "secret".to_owned(),
+    };
+    p.save(&session, "Session").unwrap();
+    assert_eq!(p.load("Session").unwrap(), session);
+}
+
+#[test]
+fn concurrent_saves_keep_all_sections() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("settings.toml");
+
+    std::thread::scope(|scope| {
+        for port in 0..8 {
+            let path = &path;
+            scope.spawn(move || {
+                TomlSectionFile::<AppConfig>::new(path)
+                    .save(&AppConfig { port }, &format!("Server{port}"))
+                    .unwrap();
+            });
+        }
+    });
+
+    let settings: toml::Table = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(settings.len(), 8);
+}
+
+#[test]
+fn concurrent_saves_through_different_paths_keep_all_sections() {
+    let dir = tempfile::tempdir().unwrap();
+    let direct = dir.path().join("settings.toml");
+    let indirect = dir.path().join(".").join("settings.toml");
+
+    std::thread::scope(|scope| {
+        for port in 0..8 {
+            let path = if port % 2 == 0 { &direct } else { &indirect };
+            scope.spawn(move || {
+                TomlSectionFile::<AppConfig>::new(path)
+                    .save(&AppConfig { port }, &format!("Server{port}"))
+                    .unwrap();
+            });
+        }
+    });
+
+    let settings: toml::Table = toml::from_str(&std::fs::read_to_string(&direct).unwrap()).unwrap();
+    assert_eq!(settings.len(), 8);
+}
@@ -0,0 +1,168 @@
+use std::{marker::PhantomData, path::Path, sync::Arc};
+
+use qeap::{LoadOrigin, OnMissing, QeapResult, Qeaper, error::Error};
+use redb::{Database, ReadableDatabase, TableDefinition, TableError};
+use serde::{Serialize, de::DeserializeOwned};
+
+#[derive(Debug, thiserror::Error)]
+pub enum KvError {
+    #[error("key-value store error: {0}")]
+    Database(#[from] redb::Error),
+    #[error("failed to encode '{0}': {1}")]
+    Encode(String, bincode::error::EncodeError),
+    #[error("failed to decode '{0}': {1}")]
+    Decode(String, bincode::error::DecodeError),
+    #[error("no entry for '{0}'")]
+    NotFound(String),
+}
+
+impl KvError {
+    fn database(cause: impl Into<redb::Error>) -> Self {
+        Self::Database(cause.into())
+    }
+}
+
+/// An embedded key-value database holding the data of one application.
+///
+/// Every application gets its own table, with one entry per persisted type. The database can
+/// only be opened once per process, so keep the store around (e.g. in a `LazyLock`) and create
+/// a [`KvPersist`] from it for each type.
+#[derive(Clone)]
+pub struct KvStore {
+    db: Arc<Database>,
+    app: String,
+}
+
+impl KvStore {
+    pub fn open(path: impl AsRef<Path>, app: impl Into<String>) -> Result<Self, KvError> {
+        let db = Database::create(path).map_err(KvError::database)?;
+
+        Ok(Self {
+            db: Arc::new(db),
+            app: app.into(),
+        })
+    }
+
+    pub fn persist<T: Default>(&self) -> KvPersist<T> {
+        KvPersist {
+            store: self.clone(),
+            on_missing: OnMissing::default(),
+            default: Some(T::default),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`KvPersist`] for data without a default, which fails to load missing entries.
+    pub fn persist_without_default<T>(&self) -> KvPersist<T> {
+        KvPersist {
+            store: self.clone(),
+            on_missing: OnMissing::Error,
+            default: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn table(&self) -> TableDefinition<'_, &'static str, &'static [u8]> {
+        TableDefinition::new(&self.app)
+    }
+}
+
+/// Persists a type as an entry in its application's table of a [`KvStore`].
+///
+/// Data is encoded with bincode, and every save is a durable write transaction, so a crash
+/// leaves either the old or the new data behind.
+pub struct KvPersist<T> {
+    store: KvStore,
+    on_missing: OnMissing,
+    default: Option<fn() -> T>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Default> KvPersist<T> {
+    pub fn on_missing(mut self, on_missing: OnMissing) -> Self {
+        self.on_missing = on_missing;
+        self
+    }
+}
+
+impl<T> Qeaper for KvPersist<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    type Output = T;
+
+    fn init(&self) -> QeapResult<()> {
+        let create_table = || -> Result<(), KvError> {
+            let tx = self.store.db.begin_write().map_err(KvError::database)?;
+            tx.open_table(self.store.table())
+                .map_err(KvError::database)?;
+            tx.commit().map_err(KvError::database)
+        };
+
+        create_table().map_err(Error::init)
+    }
+
+    fn load(&self, name: &str) -> QeapResult<Self::Output> {
+        self.load_with_origin(name).map(|(data, _)| data)
+    }
+
+    fn load_with_origin(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        let (data, origin) = self.load_deferred(name)?;
+        if origin == LoadOrigin::DefaultCreated {
+            self.save(&data, name)?;
+        }
+        Ok((data, origin))
+    }
+
+    fn load_deferred(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        let read = || -> Result<Option<T>, KvError> {
+            let tx = self.store.db.begin_read().map_err(KvError::database)?;
+            let table = match tx.open_table(self.store.table()) {
+                Ok(table) => table,
+                Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+                Err(e) => return Err(KvError::database(e)),
+            };
+
+            let Some(bytes) = table.get(name).map_err(KvError::database)? else {
+                return Ok(None);
+            };
+
+            bincode::serde::decode_from_slice(bytes.value(), bincode::config::standard())
+                .map(|(data, _)| Some(data))
+                .map_err(|e| KvError::Decode(name.to_owned(), e))
+        };
+
+        match read().map_err(Error::load)? {
+            Some(data) => Ok((data, LoadOrigin::Storage)),
+            None => match (self.on_missing, self.default) {
+                (OnMissing::CreateDefault, Some(default)) => {
+                    Ok((default(), LoadOrigin::DefaultCreated))
+                }
+                (OnMissing::UseDefault, Some(default)) => Ok((default(), LoadOrigin::Defaulted)),
+                (OnMissing::Error, _) | (_, None) => {
+                    Err(Error::not_found(KvError::NotFound(name.to_owned())))
+                }
+            },
+        }
+    }
+
+    fn save(&self, data: &Self::Output, name: &str) -> QeapResult<()> {
+        let write = || -> Result<(), KvError> {
+            let bytes = bincode::serde::encode_to_vec(data, bincode::config::standard())
+                .map_err(|e| KvError::Encode(name.to_owned(), e))?;
+
+            let tx = self.store.db.begin_write().map_err(KvError::database)?;
+            {
+                let mut table = tx
+                    .open_table(self.store.table())
+                    .map_err(KvError::database)?;
+                table
+                    .insert(name, bytes.as_slice())
+                    .map_err(KvError::database)?;
+            }
+            tx.commit().map_err(KvError::database)
+        };
+
+        write().map_err(Error::save)
+    }
+}
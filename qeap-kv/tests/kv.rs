@@ -0,0 +1,91 @@
+use qeap::{LoadOrigin, OnMissing, Qeaper};
+use qeap_kv::KvStore;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Counter {
+    value: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Session {
+    user: Option<String>,
+    history: Vec<String>,
+}
+
+#[test]
+fn round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = KvStore::open(dir.path().join("state.redb"), "app").unwrap();
+
+    let counter = store.persist::<Counter>();
+    counter.init().unwrap();
+
+    let (data, origin) = counter.load_with_origin("Counter").unwrap();
+    assert_eq!(data, Counter::default());
+    assert_eq!(origin, LoadOrigin::DefaultCreated);
+
+    counter.save(&Counter { value: 42 }, "Counter").unwrap();
+
+    let session = store.persist::<Session>();
+    let data = Session {
+        user: Some("alice".to_owned()),
+        history: vec!["login".to_owned()],
+    };
+    session.save(&data, "Session").unwrap();
+
+    assert_eq!(counter.load("Counter").unwrap(), Counter { value: 42 });
+    assert_eq!(session.load("Session").unwrap(), data);
+}
+
+#[test]
+fn survives_reopening() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state.redb");
+
+    {
+        let store = KvStore::open(&path, "app").unwrap();
+        store
+            .persist::<Counter>()
+            .save(&Counter { value: 7 }, "Counter")
+            .unwrap();
+    }
+
+    let store = KvStore::open(&path, "app").unwrap();
+    let (data, origin) = store
+        .persist::<Counter>()
+        .load_with_origin("Counter")
+        .unwrap();
+    assert_eq!(data, Counter { value: 7 });
+    assert_eq!(origin, LoadOrigin::Storage);
+}
+
+#[test]
+fn missing_entry_policies() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = KvStore::open(dir.path().join("state.redb"), "app").unwrap();
+
+    let counter = store.persist::<Counter>().on_missing(OnMissing::UseDefault);
+    let (data, origin) = counter.load_with_origin("Counter").unwrap();
+    assert_eq!(data, Counter::default());
+    assert_eq!(origin, LoadOrigin::Defaulted);
+
+    // nothing was written, so a stricter policy still finds nothing
+    let counter = store.persist::<Counter>().on_missing(OnMissing::Error);
+    assert!(counter.load("Counter").unwrap_err().is_not_found());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Token(String);
+
+#[test]
+fn without_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = KvStore::open(dir.path().join("state.redb"), "app").unwrap();
+    let token = store.persist_without_default::<Token>();
+
+    assert!(token.load("Token").unwrap_err().is_not_found());
+
+    token.save(&Token("secret".to_owned()), "Token").unwrap();
+    assert_eq!(token.load("Token").unwrap(), Token("secret".to_owned()));
+}
@@ -0,0 +1,241 @@
+use std::{
+    io,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use qeap::{LoadOrigin, OnMissing, QeapResult, Qeaper, error::Error};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Serialize, de::DeserializeOwned};
+
+const FORMAT: &str = "json";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SqliteError {
+    #[error("failed to access database '{0}': {1}")]
+    Database(String, rusqlite::Error),
+    #[error("failed to create the directory for database '{0}': {1}")]
+    CreateDir(String, io::Error),
+    #[error("failed to serialize '{0}' as {FORMAT}: {1}")]
+    Serialize(String, serde_json::Error),
+    #[error("failed to parse '{0}' as {FORMAT}: {1}")]
+    Parse(String, serde_json::Error),
+    #[error("'{0}' is stored as {1}, expected {FORMAT}")]
+    Format(String, String),
+    #[error("'{0}' is stored with version {1}, expected version {2}")]
+    Version(String, u32, u32),
+    #[error("no row for '{0}' with key '{1}'")]
+    NotFound(String, String),
+}
+
+impl SqliteError {
+    pub fn database(path: &Path, cause: rusqlite::Error) -> Self {
+        Self::Database(path.display().to_string(), cause)
+    }
+}
+
+/// Persists each type as a row of the `qeap` table in an SQLite database file.
+///
+/// Rows are identified by the type's name and an optional key, so several instances of the
+/// same type (e.g. one per user) can be stored side by side.
+pub struct SqlitePersist<T> {
+    path: PathBuf,
+    key: String,
+    version: u32,
+    on_missing: OnMissing,
+    default: Option<fn() -> T>,
+    conn: Mutex<Option<Connection>>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Default> SqlitePersist<T> {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            key: String::new(),
+            version: 0,
+            on_missing: OnMissing::default(),
+            default: Some(T::default),
+            conn: Mutex::new(None),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn on_missing(mut self, on_missing: OnMissing) -> Self {
+        self.on_missing = on_missing;
+        self
+    }
+}
+
+impl<T> SqlitePersist<T> {
+    /// Creates a `SqlitePersist` for data without a default, which fails to load missing rows.
+    pub fn without_default(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            key: String::new(),
+            version: 0,
+            on_missing: OnMissing::Error,
+            default: None,
+            conn: Mutex::new(None),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Stores the data under `key`, separately from other instances of the same type.
+    pub fn keyed(mut self, key: impl Into<String>) -> Self {
+        self.key = key.into();
+        self
+    }
+
+    /// The version saved alongside the data. Loading data saved with another version fails.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Runs `f` with the connection to the database, which is opened and set up on first use
+    /// and kept for later calls.
+    fn with_connection<R>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> Result<R, SqliteError>,
+    ) -> Result<R, SqliteError> {
+        let mut conn = self.conn.lock().unwrap_or_else(PoisonError::into_inner);
+        let conn = match &mut *conn {
+            Some(conn) => conn,
+            None => conn.insert(self.open()?),
+        };
+        f(conn)
+    }
+
+    fn open(&self) -> Result<Connection, SqliteError> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| SqliteError::CreateDir(self.path.display().to_string(), e))?;
+        }
+
+        let conn =
+            Connection::open(&self.path).map_err(|e| SqliteError::database(&self.path, e))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS qeap (
+                name TEXT NOT NULL,
+                key TEXT NOT NULL,
+                format TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                data BLOB NOT NULL,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (name, key)
+            )",
+            [],
+        )
+        .map_err(|e| SqliteError::database(&self.path, e))?;
+
+        Ok(conn)
+    }
+}
+
+impl<T> Qeaper for SqlitePersist<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    type Output = T;
+
+    fn init(&self) -> QeapResult<()> {
+        self.with_connection(|_| Ok(())).map_err(Error::init)
+    }
+
+    fn load(&self, name: &str) -> QeapResult<Self::Output> {
+        self.load_with_origin(name).map(|(data, _)| data)
+    }
+
+    fn load_with_origin(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        let (data, origin) = self.load_deferred(name)?;
+        if origin == LoadOrigin::DefaultCreated {
+            self.save(&data, name)?;
+        }
+        Ok((data, origin))
+    }
+
+    fn load_deferred(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        let row = self
+            .with_connection(|conn| {
+                conn.query_row(
+                    "SELECT format, version, data FROM qeap WHERE name = ?1 AND key = ?2",
+                    params![name, self.key],
+                    |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get(1)?,
+                            row.get::<_, Vec<u8>>(2)?,
+                        ))
+                    },
+                )
+                .optional()
+                .map_err(|e| SqliteError::database(&self.path, e))
+            })
+            .map_err(Error::load)?;
+
+        let Some((format, version, data)) = row else {
+            return match (self.on_missing, self.default) {
+                (OnMissing::CreateDefault, Some(default)) => {
+                    Ok((default(), LoadOrigin::DefaultCreated))
+                }
+                (OnMissing::UseDefault, Some(default)) => Ok((default(), LoadOrigin::Defaulted)),
+                (OnMissing::Error, _) | (_, None) => Err(Error::not_found(SqliteError::NotFound(
+                    name.to_owned(),
+                    self.key.clone(),
+                ))),
+            };
+        };
+
+        if format != FORMAT {
+            return Err(Error::load(SqliteError::Format(name.to_owned(), format)));
+        }
+
+        if version != self.version {
+            return Err(Error::load(SqliteError::Version(
+                name.to_owned(),
+                version,
+                self.version,
+            )));
+        }
+
+        let data = serde_json::from_slice(&data)
+            .map_err(|e| Error::load(SqliteError::Parse(name.to_owned(), e)))?;
+
+        Ok((data, LoadOrigin::Storage))
+    }
+
+    fn save(&self, data: &Self::Output, name: &str) -> QeapResult<()> {
+        let data = serde_json::to_vec(data)
+            .map_err(|e| Error::save(SqliteError::Serialize(name.to_owned(), e)))?;
+
+        let updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs() as i64);
+
+        self.with_connection(|conn| {
+            let tx = conn
+                .transaction()
+                .map_err(|e| SqliteError::database(&self.path, e))?;
+            tx.execute(
+                "INSERT INTO qeap (name, key, format, version, data, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                ON CONFLICT (name, key) DO UPDATE SET
+                    format = excluded.format,
+                    version = excluded.version,
+                    data = excluded.data,
+                    updated_at = excluded.updated_at",
+                params![name, self.key, FORMAT, self.version, data, updated_at],
+            )
+            .and_then(|_| tx.commit())
+            .map_err(|e| SqliteError::database(&self.path, e))
+        })
+        .map_err(Error::save)
+    }
+}
@@ -0,0 +1,98 @@
+use qeap::{LoadOrigin, OnMissing, Qeaper};
+use qeap_sqlite::SqlitePersist;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Preferences {
+    theme: String,
+    font_size: u8,
+}
+
+#[test]
+fn round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = SqlitePersist::<Preferences>::new(dir.path().join("nested/app.db"));
+    p.init().unwrap();
+
+    let (prefs, origin) = p.load_with_origin("Preferences").unwrap();
+    assert_eq!(prefs, Preferences::default());
+    assert_eq!(origin, LoadOrigin::DefaultCreated);
+
+    let prefs = Preferences {
+        theme: "dark".to_owned(),
+        font_size: 14,
+    };
+    p.save(&prefs, "Preferences").unwrap();
+
+    let (loaded, origin) = p.load_with_origin("Preferences").unwrap();
+    assert_eq!(loaded, prefs);
+    assert_eq!(origin, LoadOrigin::Storage);
+}
+
+#[test]
+fn missing_row_policies() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.db");
+
+    let p = SqlitePersist::<Preferences>::new(&path).on_missing(OnMissing::UseDefault);
+    let (prefs, origin) = p.load_with_origin("Preferences").unwrap();
+    assert_eq!(prefs, Preferences::default());
+    assert_eq!(origin, LoadOrigin::Defaulted);
+
+    // nothing was written, so a stricter policy still finds nothing
+    let p = SqlitePersist::<Preferences>::new(&path).on_missing(OnMissing::Error);
+    let err = p.load("Preferences").unwrap_err();
+    assert!(err.is_not_found(), "{err}");
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Session {
+    token: String,
+}
+
+#[test]
+fn without_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = SqlitePersist::<Session>::without_default(dir.path().join("app.db"));
+
+    assert!(p.load("Session").unwrap_err().is_not_found());
+
+    let session = Session {
+        token: "secret".to_owned(),
+    };
+    p.save(&session, "Session").unwrap();
+    assert_eq!(p.load("Session").unwrap(), session);
+}
+
+#[test]
+fn keyed_instances() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.db");
+
+    let alice = SqlitePersist::<Preferences>::new(&path).keyed("alice");
+    let bob = SqlitePersist::<Preferences>::new(&path).keyed("bob");
+    alice.init().unwrap();
+
+    let prefs = Preferences {
+        theme: "light".to_owned(),
+        font_size: 12,
+    };
+    alice.save(&prefs, "Preferences").unwrap();
+
+    assert_eq!(alice.load("Preferences").unwrap(), prefs);
+    assert_eq!(bob.load("Preferences").unwrap(), Preferences::default());
+}
+
+#[test]
+fn version_mismatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.db");
+
+    let v1 = SqlitePersist::<Preferences>::new(&path).version(1);
+    v1.init().unwrap();
+    v1.save(&Preferences::default(), "Preferences").unwrap();
+
+    let v2 = SqlitePersist::<Preferences>::new(&path).version(2);
+    let err = v2.load("Preferences").unwrap_err();
+    assert!(err.to_string().contains("version 1"), "{err}");
+}
@@ -0,0 +1,106 @@
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use crate::{Qeap, QeapResult, error::Error};
+
+/// Periodically saves shared data on a background thread until stopped or dropped.
+///
+/// A failed periodic save doesn't stop the saver; the data is saved again on the next tick.
+/// The most recent failure is kept until it's taken with [`Autosave::take_error`] or returned
+/// by [`Autosave::stop`], and a later successful save clears it.
+pub struct Autosave {
+    shared: Arc<Shared>,
+    saver: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    stopped: Mutex<bool>,
+    signal: Condvar,
+    last_error: Mutex<Option<Error>>,
+}
+
+impl Autosave {
+    pub fn spawn<T>(data: Arc<T>, interval: Duration) -> Self
+    where
+        T: Qeap + Send + Sync + 'static,
+    {
+        let shared = Arc::new(Shared {
+            stopped: Mutex::new(false),
+            signal: Condvar::new(),
+            last_error: Mutex::new(None),
+        });
+
+        let saver = {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || {
+                let mut stopped = shared
+                    .stopped
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+
+                loop {
+                    stopped = shared
+                        .signal
+                        .wait_timeout_while(stopped, interval, |stopped| !*stopped)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+
+                    if *stopped {
+                        break;
+                    }
+
+                    *shared.last_error() = T::save(&data).err();
+                }
+            })
+        };
+
+        Self {
+            shared,
+            saver: Some(saver),
+        }
+    }
+
+    /// Takes the error of the most recent periodic save, if it failed.
+    pub fn take_error(&self) -> Option<Error> {
+        self.shared.last_error().take()
+    }
+
+    /// Stops the saver, returning the error of the most recent periodic save if it failed.
+    pub fn stop(mut self) -> QeapResult<()> {
+        self.shutdown();
+        match self.take_error() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    fn shutdown(&mut self) {
+        *self
+            .shared
+            .stopped
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = true;
+        self.shared.signal.notify_one();
+
+        if let Some(saver) = self.saver.take() {
+            let _ = saver.join();
+        }
+    }
+}
+
+impl Shared {
+    fn last_error(&self) -> MutexGuard<'_, Option<Error>> {
+        self.last_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for Autosave {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
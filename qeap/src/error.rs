@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::transform::DynError;
+use crate::{QeapResult, transform::DynError};
 
-pub type FlattenErasedError = FlattenedError<Box<dyn std::error::Error>>;
+pub type FlattenErasedError = FlattenedError<DynError>;
 
 #[derive(Debug, thiserror::Error)]
 pub enum FlattenedError<E> {
@@ -17,49 +17,155 @@ pub enum FlattenedError<E> {
 pub struct Error {
     cause: DynError,
     ty: ErrorType,
+    not_found: bool,
 }
 
 #[derive(Debug, thiserror::Error)]
 #[error("{0}")]
 pub struct SimpleErr(pub String);
 
+/// Every failure from loading or saving several pieces of data, e.g. the members of a parallel bundle.
+#[derive(Debug, Default)]
+pub struct MultiError(pub Vec<Error>);
+
+impl MultiError {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps the error of a failed result, returning the value of a successful one.
+    pub fn push<T>(&mut self, result: QeapResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.0.push(err);
+                None
+            }
+        }
+    }
+
+    /// Succeeds if nothing failed. A single failure is returned as is.
+    pub fn into_result(mut self, ty: ErrorType) -> QeapResult<()> {
+        match self.0.len() {
+            0 => Ok(()),
+            1 => Err(self.0.remove(0)),
+            _ => Err(Error {
+                cause: Box::new(self),
+                ty,
+                not_found: false,
+            }),
+        }
+    }
+}
+
+impl Display for MultiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} errors", self.0.len())?;
+
+        for (i, err) in self.0.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { "; " };
+            write!(f, "{sep}{err}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for MultiError {}
+
 impl Error {
     pub fn load<E>(err: E) -> Self
     where
-        E: std::error::Error + 'static,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self {
+            cause: Box::new(err) as DynError,
+            ty: ErrorType::Load,
+            not_found: false,
+        }
+    }
+
+    /// A load error for data that was never stored, e.g. a missing file when the persistence
+    /// mechanism doesn't fall back to a default.
+    pub fn not_found<E>(err: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
     {
         Self {
             cause: Box::new(err) as DynError,
             ty: ErrorType::Load,
+            not_found: true,
         }
     }
 
     pub fn save<E>(err: E) -> Self
     where
-        E: std::error::Error + 'static,
+        E: std::error::Error + Send + Sync + 'static,
     {
         Self {
             cause: Box::new(err) as DynError,
             ty: ErrorType::Save,
+            not_found: false,
         }
     }
 
     pub fn init<E>(err: E) -> Self
     where
-        E: std::error::Error + 'static,
+        E: std::error::Error + Send + Sync + 'static,
     {
         Self {
             cause: Box::new(err) as DynError,
             ty: ErrorType::Init,
+            not_found: false,
+        }
+    }
+
+    pub fn validation<E>(err: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self {
+            cause: Box::new(err) as DynError,
+            ty: ErrorType::Validation,
+            not_found: false,
         }
     }
+
+    pub fn integrity<E>(err: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self {
+            cause: Box::new(err) as DynError,
+            ty: ErrorType::Integrity,
+            not_found: false,
+        }
+    }
+
+    pub fn ty(&self) -> ErrorType {
+        self.ty
+    }
+
+    /// Whether loading failed because there is no stored data, see [`Error::not_found`].
+    pub fn is_not_found(&self) -> bool {
+        self.not_found
+    }
+
+    /// The underlying error, e.g. to downcast to a persistence mechanism's own error type.
+    pub fn cause(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        &*self.cause
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorType {
     Load,
     Save,
     Init,
+    /// The data was loaded or is about to be saved, but failed validation.
+    Validation,
+    /// The stored data doesn't match its checksum, e.g. because of a partial write or bit rot.
+    Integrity,
 }
 
 impl Display for ErrorType {
@@ -68,6 +174,8 @@ impl Display for ErrorType {
             ErrorType::Load => write!(f, "load"),
             ErrorType::Save => write!(f, "save"),
             ErrorType::Init => write!(f, "init"),
+            ErrorType::Validation => write!(f, "validate"),
+            ErrorType::Integrity => write!(f, "verify"),
         }
     }
 }
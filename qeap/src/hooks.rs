@@ -0,0 +1,25 @@
+use std::borrow::Cow;
+
+/// Lifecycle hooks called by `derive(Qeap)` when the type is annotated with `#[qeap(hooks)]`.
+///
+/// Every hook does nothing by default, so only the ones you need have to be implemented.
+pub trait Hooks: Clone {
+    /// Called after the data was loaded, before it is validated.
+    fn after_load(&mut self) {}
+
+    /// Called before the data is validated and saved.
+    ///
+    /// Whatever is returned gets saved, e.g. an owned copy with an updated timestamp.
+    fn before_save(&self) -> Cow<'_, Self> {
+        Cow::Borrowed(self)
+    }
+
+    /// Called after the data was saved successfully.
+    fn after_save(&self) {}
+
+    /// Called when default data was created because none was stored yet, before `after_load`.
+    ///
+    /// The data is saved after the hooks ran, so changes made here are stored. Not called for
+    /// defaults that aren't saved, e.g. with [`OnMissing::UseDefault`](crate::OnMissing::UseDefault).
+    fn on_default_created(&mut self) {}
+}
@@ -22,7 +22,7 @@ impl<T: Qeap> Qeap for Mutex<T> {
         let guard = self
             .lock()
             .map_err(|e| Error::save(SimpleErr(e.to_string())))?;
-        (&*guard).save()
+        (*guard).save()
     }
 }
 
@@ -38,7 +38,7 @@ impl<T: Qeap> Qeap for RefCell<T> {
     fn save(&self) -> QeapResult<()> {
         let data = self.borrow();
 
-        (&*data).save()
+        (*data).save()
     }
 }
 
@@ -55,7 +55,7 @@ impl<T: Qeap> Qeap for RwLock<T> {
         let guard = self
             .write()
             .map_err(|e| Error::save(SimpleErr(e.to_string())))?;
-        (&*guard).save()?;
+        (*guard).save()?;
         Ok(())
     }
 }
@@ -70,7 +70,7 @@ impl<T: Qeap> Qeap for Rc<T> {
     }
 
     fn save(&self) -> QeapResult<()> {
-        T::save(&*self)
+        T::save(self)
     }
 }
 
@@ -84,7 +84,7 @@ impl<T: Qeap> Qeap for Arc<T> {
     }
 
     fn save(&self) -> QeapResult<()> {
-        T::save(&*self)
+        T::save(self)
     }
 }
 
@@ -95,14 +95,14 @@ where
     type Output = T::Output;
 
     fn init(&self) -> QeapResult<()> {
-        (&**self).init()
+        (**self).init()
     }
 
     fn load(&self, name: &str) -> QeapResult<Self::Output> {
-        (&**self).load(name)
+        (**self).load(name)
     }
 
     fn save(&self, data: &Self::Output, name: &str) -> QeapResult<()> {
-        (&**self).save(data, name)
+        (**self).save(data, name)
     }
 }
@@ -1,9 +1,16 @@
+mod autosave;
 pub mod error;
 mod handle;
+mod hooks;
 mod impls;
+pub mod name;
+mod persisted;
 pub mod transform;
 
+pub use autosave::Autosave;
 pub use handle::Handle;
+pub use hooks::Hooks;
+pub use persisted::{Persisted, SaveMode};
 // might think about adding different formats... need to make sure, if behind features, that they are additive
 
 extern crate qeap_macro;
@@ -26,6 +33,53 @@ pub trait Qeaper {
     fn init(&self) -> QeapResult<()>;
     fn load(&self, name: &str) -> QeapResult<Self::Output>;
     fn save(&self, data: &Self::Output, name: &str) -> QeapResult<()>;
+
+    /// Loads the data along with where it came from.
+    ///
+    /// Persistence mechanisms that create data when none is stored should override this.
+    fn load_with_origin(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        Ok((self.load(name)?, LoadOrigin::Storage))
+    }
+
+    /// Like [`load_with_origin`](Self::load_with_origin), but default data that would be created
+    /// is returned with [`LoadOrigin::DefaultCreated`] without saving it. The caller saves it,
+    /// e.g. once it has been validated.
+    ///
+    /// Defaults to `load_with_origin`, which may already have saved the created data.
+    fn load_deferred(&self, name: &str) -> QeapResult<(Self::Output, LoadOrigin)> {
+        self.load_with_origin(name)
+    }
+}
+
+/// Where the data returned by [`Qeaper::load_with_origin`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadOrigin {
+    /// The data was read from storage.
+    Storage,
+    /// Nothing was stored yet, so default data was created and saved, or is to be saved by the
+    /// caller of [`Qeaper::load_deferred`].
+    DefaultCreated,
+    /// Nothing was stored yet, so default data was returned without saving it.
+    Defaulted,
+}
+
+impl LoadOrigin {
+    /// Whether default data was used because nothing was stored yet.
+    pub fn is_default(self) -> bool {
+        !matches!(self, LoadOrigin::Storage)
+    }
+}
+
+/// What a persistence mechanism does when nothing is stored for the data it loads.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OnMissing {
+    /// Create the default data and save it.
+    #[default]
+    CreateDefault,
+    /// Return the default data without saving anything.
+    UseDefault,
+    /// Fail with an error for which [`Error::is_not_found`](error::Error::is_not_found) is true.
+    Error,
 }
 
 pub trait Bundle: Qeap {}
@@ -0,0 +1,56 @@
+//! Helpers for naming persisted data, used by `derive(Qeap)` on generic types.
+
+/// A stable name for a type used as a type argument of a generic `Qeap` type.
+///
+/// `derive(Qeap)` builds the persistence name of `Settings<Dev>` from `Settings` and
+/// `Dev::qeap_name()`, so data stays where it is across compiler versions and refactorings.
+/// The name must not change once data is stored under it, and different types used as
+/// arguments of the same generic type need different names.
+pub trait QeapName {
+    fn qeap_name() -> String;
+}
+
+macro_rules! impl_qeap_name {
+    ($($ty:ty),*) => {
+        $(
+            impl QeapName for $ty {
+                fn qeap_name() -> String {
+                    stringify!($ty).to_owned()
+                }
+            }
+        )*
+    };
+}
+
+impl_qeap_name!(
+    bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, String
+);
+
+macro_rules! impl_qeap_name_wrapper {
+    ($($name:ident),*) => {
+        $(
+            impl<T: QeapName> QeapName for $name<T> {
+                fn qeap_name() -> String {
+                    format!(concat!(stringify!($name), "_{}"), T::qeap_name())
+                }
+            }
+        )*
+    };
+}
+
+impl_qeap_name_wrapper!(Option, Vec, Box);
+
+/// Builds the persistence name of a generic type from its base name and the names of its
+/// type/const arguments, so `Settings` with `["Dev"]` becomes `Settings_Dev`.
+pub fn with_type_args(base: &str, args: &[&str]) -> String {
+    let mut name = String::from(base);
+
+    for arg in args {
+        if !arg.is_empty() {
+            name.push('_');
+            name.push_str(arg);
+        }
+    }
+
+    name
+}
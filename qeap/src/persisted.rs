@@ -0,0 +1,248 @@
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use crate::{Handle, Qeap, QeapResult};
+
+/// Controls when a [`Persisted`] handle writes its data back to storage.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SaveMode {
+    /// Save as part of every [`Persisted::update`] call.
+    #[default]
+    Immediate,
+    /// Save on a background thread once no update has happened for the given duration.
+    Debounced(Duration),
+}
+
+/// A cloneable, thread-safe handle to loaded `Qeap` data that persists every update.
+///
+/// Clones share the same data. Any unsaved changes are flushed when the last clone is dropped.
+pub struct Persisted<T: Qeap> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T: Qeap> {
+    shared: Arc<Shared<T>>,
+    saver: Option<JoinHandle<()>>,
+}
+
+struct Shared<T> {
+    data: RwLock<T>,
+    state: Mutex<SaveState>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct SaveState {
+    dirty: bool,
+    generation: u64,
+    shutdown: bool,
+}
+
+impl<T: Qeap> Persisted<T> {
+    pub fn load() -> QeapResult<Self> {
+        let shared = Shared::new(T::load()?);
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                shared,
+                saver: None,
+            }),
+        })
+    }
+
+    pub fn load_with(mode: SaveMode) -> QeapResult<Self>
+    where
+        T: Send + Sync + 'static,
+    {
+        let delay = match mode {
+            SaveMode::Immediate => return Self::load(),
+            SaveMode::Debounced(delay) => delay,
+        };
+
+        let shared = Shared::new(T::load()?);
+        let saver = {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || shared.run_debounced(delay))
+        };
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                shared,
+                saver: Some(saver),
+            }),
+        })
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.inner
+            .shared
+            .data
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Applies `f` to the data and persists the change according to the [`SaveMode`].
+    ///
+    /// In [`SaveMode::Debounced`] the save happens later, so this only fails in [`SaveMode::Immediate`].
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> QeapResult<R> {
+        let shared = &self.inner.shared;
+
+        let result = {
+            let mut data = shared.data.write().unwrap_or_else(PoisonError::into_inner);
+            f(&mut data)
+        };
+
+        {
+            let mut state = shared.lock_state();
+            state.dirty = true;
+            state.generation += 1;
+        }
+
+        if self.inner.saver.is_some() {
+            shared.changed.notify_one();
+        } else {
+            shared.flush()?;
+        }
+
+        Ok(result)
+    }
+
+    /// Saves the current data right away, regardless of the [`SaveMode`].
+    pub fn flush(&self) -> QeapResult<()> {
+        self.inner.shared.flush()
+    }
+}
+
+impl<T: Qeap> Clone for Persisted<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: Qeap> Qeap for Persisted<T> {
+    fn load() -> QeapResult<Self>
+    where
+        Self: Sized,
+    {
+        Persisted::load()
+    }
+
+    fn save(&self) -> QeapResult<()> {
+        self.flush()
+    }
+}
+
+impl<T> Handle for Persisted<T>
+where
+    T: Qeap + 'static,
+{
+    type In<'a> = &'a Persisted<T>;
+
+    type Out = Persisted<T>;
+
+    fn new_handle<'a>(s: Self::In<'a>) -> Self::Out {
+        Persisted::clone(s)
+    }
+}
+
+impl<T: Qeap> Drop for Inner<T> {
+    fn drop(&mut self) {
+        match self.saver.take() {
+            Some(saver) => {
+                self.shared.lock_state().shutdown = true;
+                self.shared.changed.notify_one();
+                // the saver flushes any pending changes before exiting
+                let _ = saver.join();
+            }
+            None => {
+                if self.shared.lock_state().dirty {
+                    let _ = self.shared.flush();
+                }
+            }
+        }
+    }
+}
+
+impl<T: Qeap> Shared<T> {
+    fn new(data: T) -> Arc<Self> {
+        Arc::new(Self {
+            data: RwLock::new(data),
+            state: Mutex::new(SaveState::default()),
+            changed: Condvar::new(),
+        })
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, SaveState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn flush(&self) -> QeapResult<()> {
+        self.lock_state().dirty = false;
+
+        let result = self
+            .data
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save();
+
+        if result.is_err() {
+            // keep the changes marked as unsaved so the next flush retries them
+            self.lock_state().dirty = true;
+        }
+
+        result
+    }
+
+    fn run_debounced(&self, delay: Duration) {
+        let mut state = self.lock_state();
+
+        loop {
+            while !state.dirty && !state.shutdown {
+                state = self
+                    .changed
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+
+            if state.shutdown {
+                break;
+            }
+
+            // wait until updates have been quiet for a full `delay`
+            let mut seen = state.generation;
+            loop {
+                let (next, timeout) = self
+                    .changed
+                    .wait_timeout(state, delay)
+                    .unwrap_or_else(PoisonError::into_inner);
+                state = next;
+
+                if state.shutdown || (timeout.timed_out() && state.generation == seen) {
+                    break;
+                }
+                seen = state.generation;
+            }
+
+            if state.shutdown {
+                break;
+            }
+
+            drop(state);
+            // a failed save stays dirty and is retried after the next delay
+            let _ = self.flush();
+            state = self.lock_state();
+        }
+
+        let dirty = state.dirty;
+        drop(state);
+
+        if dirty {
+            let _ = self.flush();
+        }
+    }
+}
@@ -2,7 +2,11 @@ use std::convert::Infallible;
 
 use crate::error;
 
-pub type DynError = Box<dyn std::error::Error>;
+/// The cause of a [`qeap::Error`](crate::error::Error).
+///
+/// It's `Send + Sync` so errors can be returned from other threads, such as the background
+/// saver of [`Persisted`](crate::Persisted).
+pub type DynError = Box<dyn std::error::Error + Send + Sync>;
 
 pub trait IntoFlattenedResult<T, E> {
     fn into_flattened(self) -> Result<T, error::FlattenedError<E>>;
@@ -19,3 +23,22 @@ impl<T, E> IntoFlattenedResult<T, E> for Result<T, E> {
         self.map_err(error::FlattenedError::User)
     }
 }
+
+pub trait IntoFlattenedErasedResult<T, M> {
+    fn into_flattened_erased(self) -> Result<T, error::FlattenErasedError>;
+}
+
+impl<T> IntoFlattenedErasedResult<T, Infallible> for T {
+    fn into_flattened_erased(self) -> Result<T, error::FlattenErasedError> {
+        Ok(self)
+    }
+}
+
+impl<T, E> IntoFlattenedErasedResult<T, E> for Result<T, E>
+where
+    E: Into<DynError>,
+{
+    fn into_flattened_erased(self) -> Result<T, error::FlattenErasedError> {
+        self.map_err(|e| error::FlattenedError::User(e.into()))
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use qeap::{Autosave, Qeap, QeapResult, error::Error, error::SimpleErr};
+
+static STORED: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Default)]
+struct Counter {
+    value: u32,
+}
+
+impl Qeap for Counter {
+    fn load() -> QeapResult<Self> {
+        Ok(Counter {
+            value: STORED.load(Ordering::SeqCst),
+        })
+    }
+
+    fn save(&self) -> QeapResult<()> {
+        STORED.store(self.value, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+static ATTEMPTS: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Default)]
+struct Unsaveable;
+
+impl Qeap for Unsaveable {
+    fn load() -> QeapResult<Self> {
+        Ok(Unsaveable)
+    }
+
+    fn save(&self) -> QeapResult<()> {
+        ATTEMPTS.fetch_add(1, Ordering::SeqCst);
+        Err(Error::save(SimpleErr("disk full".to_owned())))
+    }
+}
+
+#[test]
+fn saves_on_interval() {
+    let counter = Arc::new(Mutex::new(Counter::default()));
+    let autosave = Autosave::spawn(Arc::clone(&counter), Duration::from_millis(20));
+
+    counter.lock().unwrap().value = 5;
+    thread::sleep(Duration::from_millis(300));
+    assert_eq!(STORED.load(Ordering::SeqCst), 5);
+
+    assert!(autosave.stop().is_ok());
+}
+
+#[test]
+fn keeps_failed_saves() {
+    let autosave = Autosave::spawn(Arc::new(Unsaveable), Duration::from_millis(20));
+    thread::sleep(Duration::from_millis(300));
+
+    // the saver keeps trying after a failure
+    assert!(ATTEMPTS.load(Ordering::SeqCst) > 1);
+    let err = autosave.take_error().unwrap();
+    assert!(err.to_string().contains("disk full"));
+
+    thread::sleep(Duration::from_millis(100));
+    assert!(autosave.stop().is_err());
+}
+
+static SCOPED_STORED: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Default)]
+struct Session {
+    value: u32,
+}
+
+impl Qeap for Session {
+    fn load() -> QeapResult<Self> {
+        Ok(Session::default())
+    }
+
+    fn save(&self) -> QeapResult<()> {
+        SCOPED_STORED.store(self.value, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[qeap::scoped(autosave = "20ms")]
+fn run_session(session: Arc<Mutex<Session>>) {
+    session.lock().unwrap().value = 9;
+    thread::sleep(Duration::from_millis(300));
+
+    // saved while the function is still running
+    assert_eq!(SCOPED_STORED.load(Ordering::SeqCst), 9);
+}
+
+#[test]
+fn scoped_autosave_saves_while_running() {
+    run_session().unwrap();
+}
@@ -0,0 +1,124 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+    time::Duration,
+};
+
+use qeap::{Persisted, SaveMode};
+
+/// Declares a type with its own storage, so tests running in parallel don't share state.
+macro_rules! stored_type {
+    ($name:ident) => {
+        #[derive(Default)]
+        struct $name {
+            value: u32,
+        }
+
+        impl $name {
+            const STORED: &AtomicU32 = {
+                static STORED: AtomicU32 = AtomicU32::new(0);
+                &STORED
+            };
+            const SAVES: &AtomicU32 = {
+                static SAVES: AtomicU32 = AtomicU32::new(0);
+                &SAVES
+            };
+
+            fn stored() -> u32 {
+                Self::STORED.load(Ordering::SeqCst)
+            }
+
+            fn saves() -> u32 {
+                Self::SAVES.load(Ordering::SeqCst)
+            }
+        }
+
+        impl qeap::Qeap for $name {
+            fn load() -> qeap::QeapResult<Self> {
+                Ok(Self {
+                    value: Self::stored(),
+                })
+            }
+
+            fn save(&self) -> qeap::QeapResult<()> {
+                Self::STORED.store(self.value, Ordering::SeqCst);
+                Self::SAVES.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+    };
+}
+
+#[test]
+fn immediate_saves_on_every_update() {
+    stored_type!(Data);
+
+    let data = Persisted::<Data>::load().unwrap();
+    data.update(|d| d.value = 1).unwrap();
+    assert_eq!((Data::stored(), Data::saves()), (1, 1));
+
+    data.update(|d| d.value = 2).unwrap();
+    assert_eq!((Data::stored(), Data::saves()), (2, 2));
+
+    // nothing is left to flush
+    drop(data);
+    assert_eq!(Data::saves(), 2);
+}
+
+#[test]
+fn debounced_coalesces_updates() {
+    stored_type!(Data);
+
+    let data =
+        Persisted::<Data>::load_with(SaveMode::Debounced(Duration::from_millis(100))).unwrap();
+    for _ in 0..5 {
+        data.update(|d| d.value += 1).unwrap();
+    }
+    assert_eq!(Data::saves(), 0);
+
+    thread::sleep(Duration::from_millis(500));
+    assert_eq!((Data::stored(), Data::saves()), (5, 1));
+}
+
+#[test]
+fn debounced_flushes_on_drop() {
+    stored_type!(Data);
+
+    let data = Persisted::<Data>::load_with(SaveMode::Debounced(Duration::from_secs(60))).unwrap();
+    data.update(|d| d.value = 7).unwrap();
+    assert_eq!(Data::saves(), 0);
+
+    drop(data);
+    assert_eq!((Data::stored(), Data::saves()), (7, 1));
+}
+
+#[test]
+fn clones_share_state() {
+    stored_type!(Data);
+
+    let data = Persisted::<Data>::load_with(SaveMode::Debounced(Duration::from_secs(60))).unwrap();
+    let clone = data.clone();
+
+    clone.update(|d| d.value = 3).unwrap();
+    assert_eq!(data.read().value, 3);
+
+    // only the last clone flushes
+    drop(clone);
+    assert_eq!(Data::saves(), 0);
+    drop(data);
+    assert_eq!((Data::stored(), Data::saves()), (3, 1));
+}
+
+#[test]
+fn flush_saves_right_away() {
+    stored_type!(Data);
+
+    let data = Persisted::<Data>::load_with(SaveMode::Debounced(Duration::from_secs(60))).unwrap();
+    data.update(|d| d.value = 4).unwrap();
+    data.flush().unwrap();
+    assert_eq!((Data::stored(), Data::saves()), (4, 1));
+
+    // the flush left nothing to save on drop
+    drop(data);
+    assert_eq!(Data::saves(), 1);
+}
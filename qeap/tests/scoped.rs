@@ -0,0 +1,32 @@
+#[path = "ui/support.rs"]
+mod support;
+
+use support::Counter;
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
+
+#[qeap::scoped]
+#[test]
+fn test_attribute_is_kept(counter: &mut Counter) {
+    counter.value += 1;
+}
+
+#[qeap::scoped]
+fn increment(counter: &mut Counter) {
+    counter.value += 1;
+}
+
+#[test]
+fn parameters_are_saved() {
+    let saves = support::saves();
+    increment().unwrap();
+
+    // the other test saves the counter too, so only check that it moved forward
+    assert!(support::saves() > saves);
+    assert!(support::stored() > 0);
+}
@@ -0,0 +1,7 @@
+#[derive(qeap::Bundle)]
+struct App {
+    #[bundle(skip, readonly)]
+    config: Config,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(qeap::Bundle)]
+enum Settings {
+    Dev,
+    Prod,
+}
+
+fn main() {}
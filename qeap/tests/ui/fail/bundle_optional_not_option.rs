@@ -0,0 +1,7 @@
+#[derive(qeap::Bundle)]
+struct App {
+    #[bundle(optional)]
+    config: Config,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(qeap::Bundle)]
+union Settings {
+    port: u16,
+    id: u32,
+}
+
+fn main() {}
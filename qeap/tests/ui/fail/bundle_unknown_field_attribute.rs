@@ -0,0 +1,7 @@
+#[derive(qeap::Bundle)]
+struct App {
+    #[bundle(lazy)]
+    config: Config,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#[derive(Default, qeap::Qeap)]
+#[qeap(with = ())]
+#[qeap(with = ())]
+struct Settings {
+    port: u16,
+}
+
+fn main() {}
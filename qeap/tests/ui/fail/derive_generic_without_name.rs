@@ -0,0 +1,18 @@
+use std::marker::PhantomData;
+
+#[path = "../memory.rs"]
+mod memory;
+
+#[derive(Default, Clone)]
+struct Unnamed;
+
+#[derive(Default, Clone, qeap::Qeap)]
+#[qeap(with = memory::Memory::new())]
+#[qeap(bound = "E: Default + Clone + Send + 'static")]
+struct Settings<E> {
+    _env: PhantomData<E>,
+}
+
+fn main() {
+    let _ = <Settings<Unnamed> as qeap::Qeap>::load();
+}
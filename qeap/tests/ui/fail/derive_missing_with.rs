@@ -0,0 +1,6 @@
+#[derive(Default, qeap::Qeap)]
+struct Settings {
+    port: u16,
+}
+
+fn main() {}
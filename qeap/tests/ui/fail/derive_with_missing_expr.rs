@@ -0,0 +1,7 @@
+#[derive(Default, qeap::Qeap)]
+#[qeap(with)]
+struct Settings {
+    port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![deny(unused_must_use)]
+
+#[path = "../support.rs"]
+mod support;
+
+use support::Counter;
+
+#[qeap::scoped(expect)]
+#[must_use]
+fn value(counter: &Counter) -> u32 {
+    counter.value
+}
+
+fn main() {
+    value();
+}
@@ -0,0 +1,6 @@
+#[qeap::scoped(autosave = "30 seconds")]
+fn run(counter: Arc<Mutex<Counter>>) {
+    counter.lock().unwrap().value += 1;
+}
+
+fn main() {}
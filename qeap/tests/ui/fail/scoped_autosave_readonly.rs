@@ -0,0 +1,6 @@
+#[qeap::scoped(autosave = "30s")]
+fn run(#[qeap(readonly)] counter: Arc<Mutex<Counter>>) {
+    counter.lock().unwrap().value += 1;
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[qeap::scoped(autosave = "30s")]
+fn run(counter: &mut Counter) {
+    counter.value += 1;
+}
+
+fn main() {}
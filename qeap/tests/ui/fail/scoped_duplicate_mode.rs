@@ -0,0 +1,6 @@
+#[qeap::scoped(flatten, absorb)]
+fn read(counter: &Counter) -> u32 {
+    counter.value
+}
+
+fn main() {}
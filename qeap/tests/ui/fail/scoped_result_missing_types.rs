@@ -0,0 +1,6 @@
+#[qeap::scoped(flatten)]
+fn read(counter: &Counter) -> std::io::Result<u32> {
+    Ok(counter.value)
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[qeap::scoped(save_on = "err")]
+fn bump(counter: &mut Counter) -> Result<u32, String> {
+    counter.value += 1;
+    Ok(counter.value)
+}
+
+fn main() {}
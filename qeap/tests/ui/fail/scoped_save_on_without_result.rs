@@ -0,0 +1,7 @@
+#[qeap::scoped(save_on = "ok")]
+fn bump(counter: &mut Counter) -> u32 {
+    counter.value += 1;
+    counter.value
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[qeap::scoped]
+fn read(#[qeap(skip, readonly)] counter: &Counter) -> u32 {
+    counter.value
+}
+
+fn main() {}
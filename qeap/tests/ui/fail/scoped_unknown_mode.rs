@@ -0,0 +1,6 @@
+#[qeap::scoped(flattened)]
+fn read(counter: &Counter) -> u32 {
+    counter.value
+}
+
+fn main() {}
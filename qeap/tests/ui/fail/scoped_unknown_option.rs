@@ -0,0 +1,6 @@
+#[qeap::scoped(save_every = "30s")]
+fn read(counter: &Counter) -> u32 {
+    counter.value
+}
+
+fn main() {}
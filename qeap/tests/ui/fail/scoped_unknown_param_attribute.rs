@@ -0,0 +1,6 @@
+#[qeap::scoped]
+fn read(#[qeap(ignore)] counter: &Counter) -> u32 {
+    counter.value
+}
+
+fn main() {}
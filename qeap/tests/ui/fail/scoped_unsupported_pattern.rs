@@ -0,0 +1,6 @@
+#[qeap::scoped]
+fn pair((a, b): (&Counter, &Counter)) -> u32 {
+    a.value + b.value
+}
+
+fn main() {}
@@ -0,0 +1,55 @@
+use std::{any::Any, collections::BTreeMap, marker::PhantomData, sync::Mutex};
+
+use qeap::{LoadOrigin, QeapResult, Qeaper};
+
+static MEMORY: Mutex<BTreeMap<String, Box<dyn Any + Send>>> = Mutex::new(BTreeMap::new());
+
+/// Keeps saved data in memory under its persistence name.
+pub struct Memory<T>(PhantomData<T>);
+
+impl<T> Memory<T> {
+    pub fn new() -> Self {
+        Memory(PhantomData)
+    }
+}
+
+impl<T: Clone + Default + Send + 'static> Qeaper for Memory<T> {
+    type Output = T;
+
+    fn init(&self) -> QeapResult<()> {
+        Ok(())
+    }
+
+    fn load(&self, name: &str) -> QeapResult<T> {
+        self.load_with_origin(name).map(|(data, _)| data)
+    }
+
+    fn load_with_origin(&self, name: &str) -> QeapResult<(T, LoadOrigin)> {
+        let (data, origin) = self.load_deferred(name)?;
+        if origin == LoadOrigin::DefaultCreated {
+            self.save(&data, name)?;
+        }
+        Ok((data, origin))
+    }
+
+    fn load_deferred(&self, name: &str) -> QeapResult<(T, LoadOrigin)> {
+        let memory = MEMORY.lock().unwrap();
+        if let Some(data) = memory.get(name).and_then(|data| data.downcast_ref::<T>()) {
+            return Ok((data.clone(), LoadOrigin::Storage));
+        }
+
+        Ok((T::default(), LoadOrigin::DefaultCreated))
+    }
+
+    fn save(&self, data: &T, name: &str) -> QeapResult<()> {
+        MEMORY
+            .lock()
+            .unwrap()
+            .insert(name.to_owned(), Box::new(data.clone()));
+        Ok(())
+    }
+}
+
+pub fn memory_names() -> Vec<String> {
+    MEMORY.lock().unwrap().keys().cloned().collect()
+}
@@ -0,0 +1,52 @@
+#![deny(unused_variables)]
+
+#[path = "../support.rs"]
+mod support;
+
+use std::marker::PhantomData;
+
+use support::Counter;
+
+struct Service<T> {
+    step: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Service<T> {
+    #[qeap::scoped]
+    fn new(counter: &mut Counter) -> Self {
+        counter.value += 1;
+        Self {
+            step: counter.value,
+            _marker: PhantomData,
+        }
+    }
+
+    #[qeap::scoped(expect)]
+    fn with_step(#[qeap(skip)] step: u32, counter: &Counter) -> Service<T> {
+        Self {
+            step: step + counter.value,
+            _marker: PhantomData,
+        }
+    }
+
+    #[qeap::scoped]
+    #[cfg(any())]
+    fn unconfigured(&self, counter: &mut Counter) {
+        does_not_exist(counter);
+    }
+
+    #[allow(unused_variables)]
+    #[qeap::scoped]
+    fn linted(&self, counter: &mut Counter) {
+        let unused = self.step;
+    }
+}
+
+fn main() {
+    let service = Service::<String>::new().unwrap();
+    assert_eq!(service.step, 1);
+    assert_eq!(Service::<String>::with_step(2).step, 3);
+    service.linted().unwrap();
+    assert_eq!(support::stored(), 1);
+}
@@ -0,0 +1,18 @@
+#[path = "../support.rs"]
+mod support;
+
+use support::Counter;
+
+#[qeap::scoped]
+async fn bump(counter: &mut Counter) -> u32 {
+    std::future::ready(()).await;
+    counter.value += 1;
+    counter.value
+}
+
+#[qeap::scoped]
+#[tokio::main(flavor = "current_thread")]
+async fn main(counter: &Counter) {
+    assert_eq!(counter.value, 0);
+    assert_eq!(bump().await.unwrap(), 1);
+}
@@ -0,0 +1,27 @@
+#![deny(unused_must_use)]
+
+#[path = "../support.rs"]
+mod support;
+
+use support::Counter;
+
+/// Doc comments stay on the generated function.
+#[qeap::scoped]
+#[must_use]
+#[inline]
+#[allow(clippy::needless_pass_by_ref_mut)]
+fn bump(counter: &mut Counter) -> u32 {
+    counter.value += 1;
+    counter.value
+}
+
+#[qeap::scoped]
+#[cfg(any())]
+fn removed(counter: &Counter) -> u32 {
+    counter.value
+}
+
+fn main() {
+    let value = bump().unwrap();
+    assert_eq!(value, 1);
+}
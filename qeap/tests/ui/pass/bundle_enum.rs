@@ -0,0 +1,49 @@
+#[path = "../support.rs"]
+mod support;
+#[path = "../memory.rs"]
+mod memory;
+
+use qeap::{Qeap, Qeaper};
+
+use support::Counter;
+
+#[derive(Debug, Default, Clone, Qeap)]
+#[qeap(with = memory::Memory::new())]
+struct Remote {
+    url: String,
+}
+
+#[derive(qeap::Bundle)]
+#[bundle(discriminant = memory::Memory::<String>::new())]
+enum Storage {
+    #[bundle(default)]
+    Local(Counter),
+    Remote {
+        remote: Remote,
+    },
+    Disabled,
+}
+
+fn main() {
+    // nothing stored yet, so the default variant is used
+    let storage = Storage::load().unwrap();
+    assert!(matches!(storage, Storage::Local(_)));
+
+    let storage = Storage::Remote {
+        remote: Remote {
+            url: "https://example.com".to_owned(),
+        },
+    };
+    storage.save().unwrap();
+
+    let p = memory::Memory::<String>::new();
+    assert_eq!(p.load("Storage").unwrap(), "Remote");
+
+    let Storage::Remote { remote } = Storage::load().unwrap() else {
+        panic!("expected the persisted variant");
+    };
+    assert_eq!(remote.url, "https://example.com");
+
+    Storage::Disabled.save().unwrap();
+    assert!(matches!(Storage::load().unwrap(), Storage::Disabled));
+}
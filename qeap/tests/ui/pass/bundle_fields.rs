@@ -0,0 +1,99 @@
+#[path = "../support.rs"]
+mod support;
+#[path = "../memory.rs"]
+mod memory;
+
+use qeap::{Qeap, QeapResult, error::{Error, SimpleErr}};
+
+use support::Counter;
+
+#[derive(Debug, Default, Clone, PartialEq, Qeap)]
+#[qeap(with = memory::Memory::new())]
+struct Theme {
+    dark: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Qeap)]
+#[qeap(with = memory::Memory::new())]
+struct Layout {
+    columns: u8,
+}
+
+struct Missing;
+
+impl Qeap for Missing {
+    fn load() -> QeapResult<Self> {
+        Err(Error::not_found(SimpleErr("not there".to_owned())))
+    }
+
+    fn save(&self) -> QeapResult<()> {
+        unreachable!("never loaded")
+    }
+}
+
+struct Corrupt;
+
+impl Qeap for Corrupt {
+    fn load() -> QeapResult<Self> {
+        Err(Error::load(SimpleErr("unreadable".to_owned())))
+    }
+
+    fn save(&self) -> QeapResult<()> {
+        unreachable!("never loaded")
+    }
+}
+
+#[derive(Default)]
+struct Cache {
+    hits: u32,
+}
+
+#[derive(qeap::Bundle)]
+struct App {
+    counter: Counter,
+    #[bundle(readonly)]
+    theme: Theme,
+    #[bundle(optional)]
+    missing: Option<Missing>,
+    #[bundle(optional)]
+    layout: Option<Layout>,
+    #[bundle(skip)]
+    cache: Cache,
+}
+
+#[derive(qeap::Bundle)]
+struct Tuple(Counter, #[bundle(skip)] Cache);
+
+#[derive(qeap::Bundle)]
+struct Unreadable {
+    #[bundle(optional)]
+    corrupt: Option<Corrupt>,
+}
+
+fn main() {
+    let mut app = App::load().unwrap();
+    assert!(app.missing.is_none());
+    assert_eq!(app.layout, Some(Layout::default()));
+    assert_eq!(app.cache.hits, 0);
+
+    app.counter.value = 5;
+    app.theme.dark = true;
+    app.cache.hits = 10;
+    app.save().unwrap();
+
+    assert_eq!(support::stored(), 5);
+    assert_eq!(support::saves(), 1);
+    // readonly members are never written back
+    assert!(!Theme::load().unwrap().dark);
+
+    let tuple = Tuple::load().unwrap();
+    assert_eq!(tuple.0.value, 5);
+    tuple.save().unwrap();
+    assert_eq!(support::saves(), 2);
+
+    // only missing data makes an optional member `None`
+    let Err(err) = Unreadable::load() else {
+        panic!("expected the unreadable optional member to fail the bundle");
+    };
+    assert!(err.to_string().contains("unreadable"), "{err}");
+}
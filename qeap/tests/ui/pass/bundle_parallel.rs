@@ -0,0 +1,90 @@
+use std::{
+    sync::Mutex,
+    thread::{self, ThreadId},
+};
+
+use qeap::{
+    Qeap, QeapResult,
+    error::{Error, SimpleErr},
+};
+
+static THREADS: Mutex<Vec<ThreadId>> = Mutex::new(Vec::new());
+
+fn record_thread() {
+    THREADS.lock().unwrap().push(thread::current().id());
+}
+
+macro_rules! member {
+    ($name:ident, $load_err:path, $fails:expr) => {
+        #[derive(Debug)]
+        struct $name;
+
+        impl Qeap for $name {
+            fn load() -> QeapResult<Self> {
+                record_thread();
+                match $fails {
+                    true => Err($load_err(SimpleErr(stringify!($name).to_owned()))),
+                    false => Ok($name),
+                }
+            }
+
+            fn save(&self) -> QeapResult<()> {
+                record_thread();
+                match $fails {
+                    true => Err(Error::save(SimpleErr(stringify!($name).to_owned()))),
+                    false => Ok(()),
+                }
+            }
+        }
+    };
+}
+
+member!(Config, Error::load, false);
+member!(State, Error::load, false);
+member!(Remote, Error::not_found, true);
+member!(Cache, Error::load, true);
+
+#[derive(qeap::Bundle)]
+#[bundle(parallel)]
+struct App {
+    config: Config,
+    state: State,
+    #[bundle(optional)]
+    remote: Option<Remote>,
+}
+
+#[derive(qeap::Bundle)]
+#[bundle(parallel)]
+struct Broken(Config, Remote, Cache);
+
+#[derive(qeap::Bundle)]
+#[bundle(parallel)]
+struct Unreadable {
+    config: Config,
+    #[bundle(optional)]
+    cache: Option<Cache>,
+}
+
+fn main() {
+    let app = App::load().unwrap();
+    assert!(app.remote.is_none());
+    app.save().unwrap();
+
+    let threads = THREADS.lock().unwrap().clone();
+    assert_eq!(threads.len(), 5);
+    assert!(threads.iter().all(|id| *id != thread::current().id()));
+
+    // every failure is reported, not just the first
+    let Err(err) = Broken::load() else {
+        panic!("expected the failing members to fail the bundle");
+    };
+    let err = err.to_string();
+    assert!(err.contains("2 errors"), "{err}");
+    assert!(err.contains("Remote") && err.contains("Cache"), "{err}");
+
+    // only missing data makes an optional member `None`
+    let Err(err) = Unreadable::load() else {
+        panic!("expected the unreadable optional member to fail the bundle");
+    };
+    assert!(err.to_string().contains("Cache"), "{err}");
+}
@@ -0,0 +1,53 @@
+#[path = "../support.rs"]
+mod support;
+#[path = "../memory.rs"]
+mod memory;
+
+use std::cell::Cell;
+
+use qeap::Qeap;
+
+use support::Counter;
+
+#[derive(Debug, Default, Clone, Qeap)]
+#[qeap(with = memory::Memory::new())]
+struct Theme {
+    dark: bool,
+}
+
+// `Cell` isn't `Sync`, which skipped members don't need to be even when the others are
+// saved on their own threads
+#[derive(qeap::Bundle)]
+#[bundle(parallel)]
+struct App {
+    counter: Counter,
+    theme: Theme,
+    #[bundle(skip)]
+    hits: Cell<u32>,
+}
+
+#[derive(qeap::Bundle)]
+#[bundle(parallel, discriminant = memory::Memory::<String>::new())]
+enum Mode {
+    #[bundle(default)]
+    Local {
+        counter: Counter,
+        #[bundle(skip)]
+        hits: Cell<u32>,
+    },
+}
+
+fn main() {
+    let mut app = App::load().unwrap();
+    app.counter.value = 3;
+    app.hits.set(app.hits.get() + 1);
+    app.save().unwrap();
+    assert_eq!(support::stored(), 3);
+
+    let mode = Mode::load().unwrap();
+    let Mode::Local { counter, hits } = &mode;
+    assert_eq!(counter.value, 3);
+    hits.set(1);
+    mode.save().unwrap();
+    assert_eq!(support::saves(), 2);
+}
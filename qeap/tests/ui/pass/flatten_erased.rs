@@ -0,0 +1,37 @@
+#[path = "../support.rs"]
+mod support;
+
+use std::num::ParseIntError;
+
+use qeap::error::{FlattenErasedError, FlattenedError};
+use support::Counter;
+
+#[qeap::scoped(flatten_erased)]
+fn parse_into(counter: &mut Counter, #[qeap(skip)] input: &str) -> Result<u32, ParseIntError> {
+    counter.value = input.parse()?;
+    Ok(counter.value)
+}
+
+#[qeap::scoped(flatten_erased)]
+fn check(counter: &Counter) -> Result<(), std::io::Error> {
+    match counter.value {
+        0 => Err(std::io::Error::other("counter is empty")),
+        _ => Ok(()),
+    }
+}
+
+#[qeap::scoped(flatten_erased)]
+fn value(counter: &Counter) -> u32 {
+    counter.value
+}
+
+fn main() {
+    let results: [Result<(), FlattenErasedError>; 2] = [check(), parse_into("nope").map(|_| ())];
+    for result in results {
+        assert!(matches!(result, Err(FlattenedError::User(_))));
+    }
+
+    assert_eq!(parse_into("7").unwrap(), 7);
+    assert!(check().is_ok());
+    assert_eq!(value().unwrap(), 7);
+}
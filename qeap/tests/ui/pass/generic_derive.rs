@@ -0,0 +1,111 @@
+#[path = "../memory.rs"]
+mod memory;
+
+use std::marker::PhantomData;
+
+use qeap::{Qeap, name::QeapName};
+
+#[derive(Debug, Default, Clone)]
+struct Dev;
+
+impl QeapName for Dev {
+    fn qeap_name() -> String {
+        "Dev".to_owned()
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct Prod;
+
+impl QeapName for Prod {
+    fn qeap_name() -> String {
+        "Prod".to_owned()
+    }
+}
+
+// same type name in different modules, told apart by their names
+mod staging {
+    #[derive(Debug, Default, Clone)]
+    pub struct Region;
+
+    impl qeap::name::QeapName for Region {
+        fn qeap_name() -> String {
+            "StagingRegion".to_owned()
+        }
+    }
+}
+
+mod production {
+    #[derive(Debug, Default, Clone)]
+    pub struct Region;
+
+    impl qeap::name::QeapName for Region {
+        fn qeap_name() -> String {
+            "ProductionRegion".to_owned()
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Qeap)]
+#[qeap(with = memory::Memory::new())]
+#[qeap(bound = "E: Default + Clone + Send + 'static")]
+struct Settings<E> {
+    port: u16,
+    _env: PhantomData<E>,
+}
+
+#[derive(Debug, Default, Clone, Qeap)]
+#[qeap(with = memory::Memory::new())]
+struct Slots<const N: usize> {
+    used: usize,
+}
+
+#[derive(qeap::Bundle)]
+struct Pair<A, B> {
+    first: A,
+    second: B,
+}
+
+fn main() {
+    let mut dev = Settings::<Dev>::load().unwrap();
+    dev.port = 8080;
+    dev.save().unwrap();
+
+    let mut prod = Settings::<Prod>::load().unwrap();
+    prod.port = 443;
+    prod.save().unwrap();
+
+    assert_eq!(Settings::<Dev>::load().unwrap().port, 8080);
+    assert_eq!(Settings::<Prod>::load().unwrap().port, 443);
+
+    let mut pair = Pair::<Settings<Dev>, Slots<4>>::load().unwrap();
+    assert_eq!(pair.first.port, 8080);
+    pair.second.used = 3;
+    pair.save().unwrap();
+
+    assert_eq!(Slots::<4>::load().unwrap().used, 3);
+
+    let mut staging = Settings::<staging::Region>::load().unwrap();
+    staging.port = 1;
+    staging.save().unwrap();
+    let mut production = Settings::<production::Region>::load().unwrap();
+    production.port = 2;
+    production.save().unwrap();
+    assert_eq!(Settings::<staging::Region>::load().unwrap().port, 1);
+    assert_eq!(Settings::<production::Region>::load().unwrap().port, 2);
+
+    // built-in names for common type arguments
+    Settings::<Option<u8>>::load().unwrap();
+
+    assert_eq!(
+        memory::memory_names(),
+        [
+            "Settings_Dev",
+            "Settings_Option_u8",
+            "Settings_Prod",
+            "Settings_ProductionRegion",
+            "Settings_StagingRegion",
+            "Slots_4"
+        ]
+    );
+}
@@ -0,0 +1,38 @@
+#[path = "../support.rs"]
+mod support;
+
+use std::fmt::Display;
+
+use qeap::Qeap;
+use support::Counter;
+
+#[qeap::scoped]
+fn describe<T: Display>(counter: &Counter, #[qeap(skip)] label: T) -> String {
+    format!("{label}: {}", counter.value)
+}
+
+#[qeap::scoped]
+fn bump_any<Q>(data: &mut Q, #[qeap(skip)] f: impl FnOnce(&mut Q))
+where
+    Q: Qeap,
+{
+    f(data);
+}
+
+#[qeap::scoped]
+fn repeat<const N: usize>(counter: &Counter) -> [u32; N] {
+    [counter.value; N]
+}
+
+#[qeap::scoped]
+fn with_lifetime<'a>(counter: &Counter, #[qeap(skip)] name: &'a str) -> &'a str {
+    let _ = counter;
+    name
+}
+
+fn main() {
+    bump_any::<Counter>(|c| c.value = 3).unwrap();
+    assert_eq!(describe("count").unwrap(), "count: 3");
+    assert_eq!(repeat::<2>().unwrap(), [3, 3]);
+    assert_eq!(with_lifetime("counter").unwrap(), "counter");
+}
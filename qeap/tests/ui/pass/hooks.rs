@@ -0,0 +1,93 @@
+#[path = "../memory.rs"]
+mod memory;
+
+use std::borrow::Cow;
+
+use qeap::{Hooks, LoadOrigin, Qeap, QeapResult, Qeaper};
+
+#[derive(Debug, Default, Clone, Qeap)]
+#[qeap(with = memory::Memory::new(), hooks)]
+struct Config {
+    path: String,
+    revision: u32,
+    created: bool,
+    events: Vec<&'static str>,
+}
+
+impl Hooks for Config {
+    fn after_load(&mut self) {
+        self.path = self.path.trim_end_matches('/').to_owned();
+        self.events.push("after_load");
+    }
+
+    fn before_save(&self) -> Cow<'_, Self> {
+        let mut stamped = self.clone();
+        stamped.revision += 1;
+        stamped.events.clear();
+        Cow::Owned(stamped)
+    }
+
+    fn on_default_created(&mut self) {
+        self.created = true;
+        self.events.push("on_default_created");
+    }
+}
+
+/// Never stores anything, like `OnMissing::UseDefault` with nothing saved yet.
+struct Unstored;
+
+impl Qeaper for Unstored {
+    type Output = Draft;
+
+    fn init(&self) -> QeapResult<()> {
+        Ok(())
+    }
+
+    fn load(&self, name: &str) -> QeapResult<Draft> {
+        self.load_with_origin(name).map(|(data, _)| data)
+    }
+
+    fn load_with_origin(&self, _name: &str) -> QeapResult<(Draft, LoadOrigin)> {
+        Ok((Draft::default(), LoadOrigin::Defaulted))
+    }
+
+    fn save(&self, _data: &Draft, _name: &str) -> QeapResult<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Qeap)]
+#[qeap(with = Unstored, hooks)]
+struct Draft {
+    created: bool,
+}
+
+impl Hooks for Draft {
+    fn on_default_created(&mut self) {
+        self.created = true;
+    }
+}
+
+fn main() {
+    // nothing was created, so there's nothing to react to
+    assert!(!Draft::load().unwrap().created);
+
+    let mut config = Config::load().unwrap();
+    assert!(config.created);
+    assert_eq!(config.events, ["on_default_created", "after_load"]);
+    // created data is stored once the hooks changed it
+    let stored: Config = memory::Memory::new().load("Config").unwrap();
+    assert!(stored.created);
+
+    config.path = "/srv/data/".to_owned();
+    config.created = false;
+    config.save().unwrap();
+    // the value returned by `before_save` is what gets stored
+    assert_eq!(config.revision, 0);
+
+    let config = Config::load().unwrap();
+    assert!(!config.created);
+    assert_eq!(config.path, "/srv/data");
+    assert_eq!(config.revision, 1);
+    assert_eq!(config.events, ["after_load"]);
+}
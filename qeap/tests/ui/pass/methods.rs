@@ -0,0 +1,38 @@
+#[path = "../support.rs"]
+mod support;
+
+use support::Counter;
+
+struct Service {
+    step: u32,
+}
+
+impl Service {
+    #[qeap::scoped]
+    pub fn advance(&self, counter: &mut Counter) -> u32 {
+        counter.value += self.step;
+        counter.value
+    }
+
+    #[qeap::scoped(flatten)]
+    fn reconfigure(&mut self, counter: &Counter, #[qeap(skip)] factor: u32) -> Result<u32, String> {
+        self.step = counter.value * factor;
+        Ok(self.step)
+    }
+
+    #[qeap::scoped(expect)]
+    fn finish(mut self, counter: &mut Counter) -> u32 {
+        self.step += 1;
+        counter.value += self.step;
+        counter.value
+    }
+}
+
+fn main() {
+    let mut service = Service { step: 2 };
+    assert_eq!(service.advance().unwrap(), 2);
+    assert_eq!(service.reconfigure(3).unwrap(), 6);
+    assert_eq!(service.advance().unwrap(), 8);
+    assert_eq!(service.finish(), 15);
+    assert_eq!(support::stored(), 15);
+}
@@ -0,0 +1,112 @@
+#[path = "../support.rs"]
+mod support;
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use support::Counter;
+
+#[qeap::scoped]
+fn read(counter: &Counter) -> u32 {
+    counter.value
+}
+
+#[qeap::scoped]
+fn read_shared(#[qeap(readonly)] counter: Rc<Counter>) -> u32 {
+    counter.value
+}
+
+#[qeap::scoped]
+fn read_rc(counter: Rc<Counter>) -> u32 {
+    counter.value
+}
+
+#[qeap::scoped]
+fn read_arc(counter: std::sync::Arc<Counter>) -> u32 {
+    counter.value
+}
+
+#[qeap::scoped]
+fn bump_rc(counter: Rc<RefCell<Counter>>) {
+    counter.borrow_mut().value += 1;
+}
+
+#[qeap::scoped]
+fn bump_arc(counter: Arc<Mutex<Counter>>) {
+    counter.lock().unwrap().value += 1;
+}
+
+#[qeap::scoped]
+fn save_rc(#[qeap(save)] counter: Rc<Counter>) -> u32 {
+    counter.value
+}
+
+/// Only named like a `Result`, so it's returned like any other value.
+struct ResultSummary(u32);
+
+#[qeap::scoped]
+fn summarize(counter: &Counter) -> ResultSummary {
+    ResultSummary(counter.value)
+}
+
+#[qeap::scoped]
+fn read_and_save(#[qeap(save)] counter: &Counter) -> u32 {
+    counter.value
+}
+
+#[qeap::scoped(save_on = "ok")]
+fn bump_if(counter: &mut Counter, #[qeap(skip)] allowed: bool) -> Result<u32, String> {
+    counter.value += 1;
+    if allowed {
+        Ok(counter.value)
+    } else {
+        Err("not allowed".to_string())
+    }
+}
+
+#[qeap::scoped(flatten, save_on = "ok")]
+fn bump_flattened(counter: &mut Counter, #[qeap(skip)] allowed: bool) -> Result<u32, String> {
+    bump_if_inner(counter, allowed)
+}
+
+fn bump_if_inner(counter: &mut Counter, allowed: bool) -> Result<u32, String> {
+    counter.value += 1;
+    allowed.then_some(counter.value).ok_or_else(|| "not allowed".to_string())
+}
+
+fn main() {
+    read().unwrap();
+    read_shared().unwrap();
+    assert_eq!(support::saves(), 0);
+
+    read_and_save().unwrap();
+    assert_eq!(support::saves(), 1);
+
+    assert!(bump_if(false).unwrap().is_err());
+    assert_eq!(support::saves(), 1);
+    assert_eq!(support::stored(), 0);
+
+    assert_eq!(bump_if(true).unwrap(), Ok(1));
+    assert_eq!(support::saves(), 2);
+
+    assert!(bump_flattened(false).is_err());
+    assert_eq!(support::stored(), 1);
+    assert_eq!(bump_flattened(true).unwrap(), 2);
+    assert_eq!(support::stored(), 2);
+
+    let saves = support::saves();
+    assert_eq!(read_rc().unwrap(), 2);
+    assert_eq!(read_arc().unwrap(), 2);
+    assert_eq!(summarize().unwrap().0, 2);
+    assert_eq!(support::saves(), saves);
+
+    bump_rc().unwrap();
+    bump_arc().unwrap();
+    assert_eq!(support::stored(), 4);
+
+    save_rc().unwrap();
+    assert_eq!(support::saves(), saves + 3);
+}
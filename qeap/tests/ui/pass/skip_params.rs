@@ -0,0 +1,30 @@
+#[path = "../support.rs"]
+mod support;
+
+use std::fmt::Debug;
+
+use support::Counter;
+
+#[qeap::scoped]
+fn record<T: Debug>(
+    counter: &mut Counter,
+    #[qeap(skip)] owned: String,
+    #[qeap(skip)] borrowed: &str,
+    #[qeap(skip)] log: &mut Vec<String>,
+    #[qeap(skip)] extra: T,
+) -> usize {
+    counter.value += 1;
+    log.push(format!("{owned} {borrowed} {extra:?} {}", counter.value));
+    log.len()
+}
+
+fn main() {
+    let mut log = Vec::new();
+
+    assert_eq!(record("a".to_owned(), "b", &mut log, 1u8).unwrap(), 1);
+    assert_eq!(record(String::new(), "c", &mut log, Some('d')).unwrap(), 2);
+
+    assert_eq!(log, ["a b 1 1", " c Some('d') 2"]);
+    assert_eq!(support::stored(), 2);
+    assert_eq!(support::saves(), 2);
+}
@@ -0,0 +1,17 @@
+#[path = "../support.rs"]
+mod support;
+
+use support::Counter;
+
+/// # Safety
+/// `ptr` must be valid for reads.
+#[qeap::scoped]
+unsafe fn add_from(counter: &mut Counter, #[qeap(skip)] ptr: *const u32) {
+    counter.value += unsafe { *ptr };
+}
+
+fn main() {
+    let amount = 4;
+    unsafe { add_from(&amount) }.unwrap();
+    assert_eq!(support::stored(), 4);
+}
@@ -0,0 +1,64 @@
+#[path = "../memory.rs"]
+mod memory;
+
+use qeap::{Qeap, error::ErrorType};
+
+#[derive(Debug, thiserror::Error)]
+#[error("port {0} is out of range")]
+struct InvalidPort(u16);
+
+#[derive(Debug, Clone, Qeap)]
+#[qeap(with = memory::Memory::new(), validate = Self::validate)]
+struct Config {
+    port: u16,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config { port: 8080 }
+    }
+}
+
+impl Config {
+    fn validate(&self) -> Result<(), InvalidPort> {
+        match self.port {
+            0 => Err(InvalidPort(self.port)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Qeap)]
+#[qeap(with = memory::Memory::new(), validate = Unset::validate)]
+struct Unset {
+    port: u16,
+}
+
+impl Unset {
+    fn validate(&self) -> Result<(), InvalidPort> {
+        match self.port {
+            0 => Err(InvalidPort(self.port)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn main() {
+    let mut config = Config::load().unwrap();
+    assert_eq!(config.port, 8080);
+
+    config.port = 0;
+    let err = config.save().unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Validation);
+    assert!(err.to_string().contains("port 0 is out of range"));
+
+    // invalid data that made it into storage is caught on load
+    qeap::Qeaper::save(&memory::Memory::new(), &config, "Config").unwrap();
+    let err = Config::load().unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Validation);
+
+    // an invalid default fails to load and is never stored
+    let err = Unset::load().unwrap_err();
+    assert_eq!(err.ty(), ErrorType::Validation);
+    assert!(!memory::memory_names().contains(&"Unset".to_owned()));
+}
@@ -0,0 +1,22 @@
+#[path = "../support.rs"]
+mod support;
+
+mod counters {
+    use crate::support::Counter;
+
+    #[qeap::scoped]
+    pub fn bump(counter: &mut Counter) {
+        counter.value += 1;
+    }
+
+    #[qeap::scoped]
+    pub(crate) fn double(counter: &mut Counter) {
+        counter.value *= 2;
+    }
+}
+
+fn main() {
+    counters::bump().unwrap();
+    counters::double().unwrap();
+    assert_eq!(support::stored(), 2);
+}
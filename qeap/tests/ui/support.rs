@@ -0,0 +1,33 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use qeap::{Qeap, QeapResult};
+
+static STORED: AtomicU32 = AtomicU32::new(0);
+static SAVES: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Debug, Default)]
+pub struct Counter {
+    pub value: u32,
+}
+
+impl Qeap for Counter {
+    fn load() -> QeapResult<Self> {
+        Ok(Counter {
+            value: STORED.load(Ordering::SeqCst),
+        })
+    }
+
+    fn save(&self) -> QeapResult<()> {
+        STORED.store(self.value, Ordering::SeqCst);
+        SAVES.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+pub fn stored() -> u32 {
+    STORED.load(Ordering::SeqCst)
+}
+
+pub fn saves() -> u32 {
+    SAVES.load(Ordering::SeqCst)
+}
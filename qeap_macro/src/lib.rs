@@ -1,24 +1,32 @@
 // going through youtube tutorial first because I like the way this guy handles proc macros...
 
-use std::ops::Range;
-
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use syn::{
-    Attribute, Data, DeriveInput, Expr, GenericArgument, Ident, Index, ItemFn, PatType,
-    PathArguments, PathSegment, ReturnType, Token, Type, TypeReference, parse::Parse,
-    parse_macro_input,
+    Attribute, Data, DeriveInput, Expr, ExprLit, Field, Fields, FnArg, GenericArgument,
+    GenericParam, Generics, Ident, Index, ItemFn, Lit, LitStr, Member, Meta, PatType,
+    PathArguments, PathSegment, Receiver, ReturnType, Token, Type, TypeReference, Visibility,
+    WherePredicate, parse::Parse, parse_macro_input, parse_quote, punctuated::Punctuated,
+    spanned::Spanned,
 };
 
-use quote::{ToTokens, quote};
+use quote::{ToTokens, format_ident, quote};
 
 struct QeapAttributes {
     with: Option<Expr>,
+    bounds: Vec<WherePredicate>,
+    validate: Option<syn::Path>,
+    hooks: bool,
 }
 
 impl QeapAttributes {
-    fn parse(attrs: &[Attribute]) -> Self {
-        let mut qeap_attrs = Self { with: None };
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut qeap_attrs = Self {
+            with: None,
+            bounds: Vec::new(),
+            validate: None,
+            hooks: false,
+        };
 
         for attr in attrs {
             if !attr.path().is_ident("qeap") {
@@ -27,17 +35,45 @@ impl QeapAttributes {
 
             attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("with") {
+                    if qeap_attrs.with.is_some() {
+                        return Err(meta.error("`with` can only be specified once"));
+                    }
+
                     let _ = meta.input.parse::<Token![=]>()?;
 
                     qeap_attrs.with = Some(meta.input.parse::<Expr>()?);
-                }
 
-                Ok(())
-            })
-            .expect("with = <expr>");
+                    Ok(())
+                } else if meta.path.is_ident("bound") {
+                    let bound = meta.value()?.parse::<LitStr>()?;
+                    let predicates = bound.parse_with(
+                        Punctuated::<WherePredicate, Token![,]>::parse_terminated,
+                    )?;
+
+                    qeap_attrs.bounds.extend(predicates);
+
+                    Ok(())
+                } else if meta.path.is_ident("validate") {
+                    if qeap_attrs.validate.is_some() {
+                        return Err(meta.error("`validate` can only be specified once"));
+                    }
+
+                    qeap_attrs.validate = Some(meta.value()?.parse::<syn::Path>()?);
+
+                    Ok(())
+                } else if meta.path.is_ident("hooks") {
+                    qeap_attrs.hooks = true;
+
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "unknown qeap attribute, expected `with = <expr>`, `bound = \"...\"`, `validate = <path>` or `hooks`",
+                    ))
+                }
+            })?;
         }
 
-        qeap_attrs
+        Ok(qeap_attrs)
     }
 }
 
@@ -45,128 +81,737 @@ impl QeapAttributes {
 pub fn derive_qeap(input: TokenStream) -> TokenStream {
     let c = parse_macro_input!(input as DeriveInput);
 
-    let qeap_attrs = QeapAttributes::parse(&c.attrs);
+    let out = match expand_qeap(&c) {
+        Ok(out) => out,
+        Err(e) => e.to_compile_error(),
+    };
+
+    out.into()
+}
+
+fn expand_qeap(c: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let qeap_attrs = QeapAttributes::parse(&c.attrs)?;
 
     let type_name = &c.ident;
     let type_name_str = c.ident.to_string();
 
-    let persistence_mechanism_create = qeap_attrs.with.expect("with = <expr> is required");
+    let Some(persistence_mechanism_create) = qeap_attrs.with else {
+        return Err(syn::Error::new(
+            type_name.span(),
+            "deriving Qeap requires a persistence mechanism, e.g. `#[qeap(with = <expr>)]`",
+        ));
+    };
 
-    let out = quote! {
-        impl ::qeap::Qeap for #type_name {
+    let mut generics = c.generics.clone();
+    let bounds = name_bounds(&c.generics)
+        .into_iter()
+        .chain(qeap_attrs.bounds)
+        .collect::<Vec<_>>();
+    if !bounds.is_empty() {
+        generics.make_where_clause().predicates.extend(bounds);
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let name = persistence_name(&c.generics, &type_name_str);
+
+    let validate = |data: proc_macro2::TokenStream| {
+        qeap_attrs.validate.as_ref().map(|validate| {
+            quote! {
+                #validate(#data).map_err(::qeap::error::Error::validation)?;
+            }
+        })
+    };
+    let validate_loaded = validate(quote! { &data });
+    let validate_saved = validate(quote! { data });
+
+    let (load, save) = if qeap_attrs.hooks {
+        let load = quote! {
+            let (mut data, origin) = ::qeap::Qeaper::load_deferred(&p, #name)?;
+            if origin == ::qeap::LoadOrigin::DefaultCreated {
+                ::qeap::Hooks::on_default_created(&mut data);
+            }
+            ::qeap::Hooks::after_load(&mut data);
+        };
+        let save = quote! {
+            let data = ::qeap::Hooks::before_save(self);
+            let data = &*data;
+            #validate_saved
+            let p = #persistence_mechanism_create;
+            ::qeap::Qeaper::init(&p)?;
+            ::qeap::Qeaper::save(&p, data, #name)?;
+            ::qeap::Hooks::after_save(self);
+            Ok(())
+        };
+
+        (load, save)
+    } else {
+        let load = quote! {
+            let (data, origin) = ::qeap::Qeaper::load_deferred(&p, #name)?;
+        };
+        let save = quote! {
+            let data = self;
+            #validate_saved
+            let p = #persistence_mechanism_create;
+            ::qeap::Qeaper::init(&p)?;
+            ::qeap::Qeaper::save(&p, data, #name)
+        };
+
+        (load, save)
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::qeap::Qeap for #type_name #ty_generics #where_clause {
             fn load() -> qeap::QeapResult<Self>
             where
                 Self: Sized
             {
                 let p = #persistence_mechanism_create;
                 ::qeap::Qeaper::init(&p)?;
-                ::qeap::Qeaper::load(&p, #type_name_str)
+                #load
+                #validate_loaded
+                // created data is only stored once it's known to be valid
+                if origin == ::qeap::LoadOrigin::DefaultCreated {
+                    ::qeap::Qeaper::save(&p, &data, #name)?;
+                }
+                Ok(data)
             }
 
             fn save(&self) -> qeap::QeapResult<()> {
-                let p = #persistence_mechanism_create;
-                ::qeap::Qeaper::init(&p)?;
-                ::qeap::Qeaper::save(&p, self, #type_name_str)
+                #save
+            }
+        }
+    })
+}
+
+/// The name a derived type is persisted under.
+///
+/// Generic types append the names of their type arguments (see `qeap::name::QeapName`) and
+/// their const arguments, so `Settings<Dev>` and `Settings<Prod>` don't share storage.
+/// Lifetimes don't affect the name.
+fn persistence_name(generics: &Generics, type_name: &str) -> proc_macro2::TokenStream {
+    let args = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(t) => {
+                let ident = &t.ident;
+                Some(quote! { &<#ident as ::qeap::name::QeapName>::qeap_name() })
+            }
+            GenericParam::Const(c) => {
+                let ident = &c.ident;
+                Some(quote! { &::std::string::ToString::to_string(&#ident) })
+            }
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    if args.is_empty() {
+        quote! { #type_name }
+    } else {
+        quote! { &::qeap::name::with_type_args(#type_name, &[#(#args),*]) }
+    }
+}
+
+/// Bounds every type parameter by `QeapName`, which [`persistence_name`] needs.
+fn name_bounds(generics: &Generics) -> Vec<WherePredicate> {
+    generics
+        .type_params()
+        .map(|t| {
+            let ident = &t.ident;
+            parse_quote! { #ident: ::qeap::name::QeapName }
+        })
+        .collect()
+}
+
+struct BundleAttributes {
+    discriminant: Option<Expr>,
+    parallel: bool,
+}
+
+impl BundleAttributes {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut bundle_attrs = Self {
+            discriminant: None,
+            parallel: false,
+        };
+
+        for attr in attrs {
+            if !attr.path().is_ident("bundle") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("discriminant") {
+                    if bundle_attrs.discriminant.is_some() {
+                        return Err(meta.error("`discriminant` can only be specified once"));
+                    }
+
+                    bundle_attrs.discriminant = Some(meta.value()?.parse::<Expr>()?);
+
+                    Ok(())
+                } else if meta.path.is_ident("parallel") {
+                    bundle_attrs.parallel = true;
+
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "unknown bundle attribute, expected `discriminant = <expr>` or `parallel`",
+                    ))
+                }
+            })?;
+        }
+
+        Ok(bundle_attrs)
+    }
+}
+
+#[derive(Default)]
+struct BundleFieldAttributes {
+    skip: bool,
+    readonly: bool,
+    optional: bool,
+}
+
+impl BundleFieldAttributes {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut field_attrs = Self::default();
+
+        for attr in attrs {
+            if !attr.path().is_ident("bundle") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    field_attrs.skip = true;
+                } else if meta.path.is_ident("readonly") {
+                    field_attrs.readonly = true;
+                } else if meta.path.is_ident("optional") {
+                    field_attrs.optional = true;
+                } else {
+                    return Err(meta.error(
+                        "unknown bundle field attribute, expected `skip`, `readonly` or `optional`",
+                    ));
+                }
+
+                Ok(())
+            })?;
+
+            if field_attrs.skip && (field_attrs.readonly || field_attrs.optional) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "`skip` can't be combined with `readonly` or `optional`",
+                ));
+            }
+        }
+
+        Ok(field_attrs)
+    }
+}
+
+struct BundleField {
+    member: Member,
+    ty: Type,
+    attrs: BundleFieldAttributes,
+}
+
+impl BundleField {
+    fn from_field(index: usize, field: &Field) -> syn::Result<Self> {
+        let attrs = BundleFieldAttributes::parse(&field.attrs)?;
+
+        if attrs.optional && option_inner_type(&field.ty).is_none() {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "`optional` bundle fields must be an `Option<T>`",
+            ));
+        }
+
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+
+        Ok(Self {
+            member,
+            ty: field.ty.clone(),
+            attrs,
+        })
+    }
+
+    fn from_fields(fields: &Fields) -> syn::Result<Vec<Self>> {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| Self::from_field(i, f))
+            .collect()
+    }
+
+    /// The type that is loaded through `Qeap` for this field, if any.
+    fn qeap_type(&self) -> Option<&Type> {
+        if self.attrs.skip {
+            None
+        } else if self.attrs.optional {
+            Some(option_inner_type(&self.ty).expect("checked when parsing the field"))
+        } else {
+            Some(&self.ty)
+        }
+    }
+
+    /// The bounds the field's type has to satisfy for the bundle to be `Qeap`.
+    fn bounds(&self, parallel: bool) -> Vec<WherePredicate> {
+        let Some(ty) = self.qeap_type() else {
+            let ty = &self.ty;
+            return vec![parse_quote! { #ty: ::std::default::Default }];
+        };
+
+        let mut bounds = vec![parse_quote! { #ty: ::qeap::Qeap }];
+        if parallel {
+            // loaded members are sent back from their thread, saved ones are shared with it
+            bounds.push(parse_quote! { #ty: ::std::marker::Send });
+            if self.is_saved() {
+                bounds.push(parse_quote! { #ty: ::std::marker::Sync });
             }
         }
+
+        bounds
+    }
+
+    fn load(&self) -> proc_macro2::TokenStream {
+        if self.attrs.skip {
+            quote! { ::std::default::Default::default() }
+        } else if self.attrs.optional {
+            let load = optional_result(quote! { ::qeap::Qeap::load() });
+            quote! { #load? }
+        } else {
+            quote! { ::qeap::Qeap::load()? }
+        }
+    }
+
+    fn is_saved(&self) -> bool {
+        !self.attrs.skip && !self.attrs.readonly
+    }
+
+    /// Saves the field, given an expression borrowing it.
+    fn save(&self, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if !self.is_saved() {
+            quote! {}
+        } else if self.attrs.optional {
+            quote! {
+                if let ::std::option::Option::Some(value) = #value {
+                    ::qeap::Qeap::save(value)?;
+                }
+            }
+        } else {
+            quote! { ::qeap::Qeap::save(#value)?; }
+        }
+    }
+
+    fn binding(&self) -> Ident {
+        match &self.member {
+            Member::Named(ident) => format_ident!("__qeap_{}", ident),
+            Member::Unnamed(index) => format_ident!("__qeap_{}", index.index),
+        }
+    }
+}
+
+/// Loads `fields` and builds `path` from them, evaluating to a `QeapResult`.
+///
+/// In parallel mode each member is loaded on its own scoped thread and every failure is reported.
+fn load_fields(
+    path: proc_macro2::TokenStream,
+    fields: &[BundleField],
+    parallel: bool,
+) -> proc_macro2::TokenStream {
+    let members = fields.iter().map(|f| &f.member);
+    let loaded = fields.iter().filter(|f| !f.attrs.skip).collect::<Vec<_>>();
+
+    if !parallel || loaded.is_empty() {
+        let loads = fields.iter().map(BundleField::load);
+        return quote! { Ok(#path { #(#members: #loads),* }) };
+    }
+
+    let bindings = loaded.iter().map(|f| f.binding()).collect::<Vec<_>>();
+    let types = loaded.iter().map(|f| f.qeap_type());
+    let collects = loaded.iter().map(|f| {
+        let binding = f.binding();
+        if f.attrs.optional {
+            let result = optional_result(quote! { #binding });
+            quote! { let #binding = __qeap_errors.push(#result); }
+        } else {
+            quote! { let #binding = __qeap_errors.push(#binding); }
+        }
+    });
+    let values = fields.iter().map(|f| {
+        let binding = f.binding();
+        if f.attrs.skip {
+            quote! { ::std::default::Default::default() }
+        } else {
+            // every failure was returned above
+            quote! { ::std::option::Option::unwrap(#binding) }
+        }
+    });
+
+    quote! {{
+        let (#(#bindings,)*) = ::std::thread::scope(|__qeap_scope| {
+            #(
+                let #bindings = __qeap_scope.spawn(|| -> ::qeap::QeapResult<#types> {
+                    ::qeap::Qeap::load()
+                });
+            )*
+            (#(
+                #bindings.join().unwrap_or_else(|panic| ::std::panic::resume_unwind(panic)),
+            )*)
+        });
+
+        let mut __qeap_errors = ::qeap::error::MultiError::new();
+        #(#collects)*
+        __qeap_errors.into_result(::qeap::error::ErrorType::Load)?;
+
+        Ok(#path { #(#members: #values),* })
+    }}
+}
+
+/// Saves `fields`, given expressions borrowing each of them.
+///
+/// In parallel mode each member is saved on its own scoped thread and every failure is reported.
+fn save_fields(
+    fields: &[BundleField],
+    values: Vec<proc_macro2::TokenStream>,
+    parallel: bool,
+) -> proc_macro2::TokenStream {
+    let saved = fields
+        .iter()
+        .zip(values)
+        .filter(|(f, _)| f.is_saved())
+        .collect::<Vec<_>>();
+
+    if !parallel || saved.is_empty() {
+        let saves = saved.into_iter().map(|(f, value)| f.save(value));
+        return quote! { #(#saves)* };
+    }
+
+    let values = saved.iter().map(|(_, value)| value);
+    let saves = saved.iter().map(|(f, _)| f.save(quote! { __qeap_f }));
+
+    quote! {
+        let mut __qeap_errors = ::qeap::error::MultiError::new();
+        ::std::thread::scope(|__qeap_scope| {
+            // only the member's reference is moved into its thread, so the rest of the bundle
+            // doesn't have to be `Sync`
+            let handles = [#({
+                let __qeap_f = #values;
+                __qeap_scope.spawn(move || -> ::qeap::QeapResult<()> {
+                    #saves
+                    Ok(())
+                })
+            },)*];
+
+            for handle in handles {
+                __qeap_errors.push(
+                    handle.join().unwrap_or_else(|panic| ::std::panic::resume_unwind(panic)),
+                );
+            }
+        });
+        __qeap_errors.into_result(::qeap::error::ErrorType::Save)?;
+    }
+}
+
+/// Turns the `QeapResult<T>` of loading an optional member into a `QeapResult<Option<T>>`,
+/// which is `None` only when nothing is stored.
+fn optional_result(result: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        match #result {
+            ::std::result::Result::Ok(value) => ::std::result::Result::Ok(::std::option::Option::Some(value)),
+            ::std::result::Result::Err(err) if err.is_not_found() => ::std::result::Result::Ok(::std::option::Option::None),
+            ::std::result::Result::Err(err) => ::std::result::Result::Err(err),
+        }
+    }
+}
+
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
     };
 
-    out.into()
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    match args.args.first()? {
+        GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
 }
 
-enum BundleStrategy {
-    TupleStruct(Range<usize>),
-    Struct(Vec<Ident>),
+struct BundleVariant {
+    ident: Ident,
+    fields: Vec<BundleField>,
+}
+
+impl BundleVariant {
+    fn load(&self, parallel: bool) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        load_fields(quote! { Self::#ident }, &self.fields, parallel)
+    }
+
+    fn destructure(&self) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        let saved = self.fields.iter().filter(|f| f.is_saved());
+        let members = saved.clone().map(|f| &f.member);
+        let bindings = saved.map(BundleField::binding);
+
+        quote! { Self::#ident { #(#members: #bindings,)* .. } }
+    }
+}
+
+enum BundleData {
+    Struct(Vec<BundleField>),
+    Enum {
+        discriminant: Expr,
+        variants: Vec<BundleVariant>,
+        default: Option<Ident>,
+    },
 }
 
 struct Bundle {
     type_name: Ident,
-    strategy: BundleStrategy,
+    generics: Generics,
+    parallel: bool,
+    data: BundleData,
 }
 
-impl ToTokens for Bundle {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        let type_name = &self.type_name;
-        let t = match &self.strategy {
-            BundleStrategy::TupleStruct(range) => {
-                let inits = range.clone().map(|_| quote! { ::qeap::Qeap::load()? });
-                let field_names = range.clone().map(|i| Index::from(i));
-                quote! {
-                    impl ::qeap::Qeap for #type_name {
-                        fn load() -> qeap::QeapResult<Self>
-                        where
-                            Self: Sized
-                        {
-                            Ok(Self(#(#inits),*))
-                        }
-
-                        fn save(&self) -> qeap::QeapResult<()> {
-                            #(
-                                ::qeap::Qeap::save(&self.#field_names)?;
-                            )*
-                            Ok(())
-                        }
-                    }
+impl Bundle {
+    fn from_derive_input(c: &DeriveInput) -> syn::Result<Self> {
+        let bundle_attrs = BundleAttributes::parse(&c.attrs)?;
+
+        let data = match &c.data {
+            Data::Struct(s) => {
+                if let Some(discriminant) = bundle_attrs.discriminant {
+                    return Err(syn::Error::new_spanned(
+                        discriminant,
+                        "`discriminant` is only supported on enum bundles",
+                    ));
                 }
+
+                BundleData::Struct(BundleField::from_fields(&s.fields)?)
             }
-            BundleStrategy::Struct(idents) => {
-                quote! {
-                    impl ::qeap::Qeap for #type_name {
-                        fn load() -> qeap::QeapResult<Self>
-                        where
-                            Self: Sized
-                        {
-                            Ok(Self {
-                                #(
-                                    #idents: ::qeap::Qeap::load()?
-                                ),*
-                            })
+            Data::Enum(e) => {
+                let Some(discriminant) = bundle_attrs.discriminant else {
+                    return Err(syn::Error::new(
+                        e.enum_token.span,
+                        "enum bundles require a persisted variant, e.g. `#[bundle(discriminant = <expr>)]`",
+                    ));
+                };
+
+                let mut default = None;
+                let mut variants = Vec::new();
+
+                for variant in &e.variants {
+                    for attr in &variant.attrs {
+                        if !attr.path().is_ident("bundle") {
+                            continue;
                         }
 
-                        fn save(&self) -> qeap::QeapResult<()> {
-                            #(
-                                ::qeap::Qeap::save(&self.#idents)?;
-                            )*
+                        attr.parse_nested_meta(|meta| {
+                            if !meta.path.is_ident("default") {
+                                return Err(meta.error(
+                                    "unknown bundle variant attribute, expected `default`",
+                                ));
+                            }
+
+                            if default.is_some() {
+                                return Err(meta.error("only one variant can be the `default`"));
+                            }
+
+                            default = Some(variant.ident.clone());
+
                             Ok(())
-                        }
+                        })?;
                     }
+
+                    variants.push(BundleVariant {
+                        ident: variant.ident.clone(),
+                        fields: BundleField::from_fields(&variant.fields)?,
+                    });
+                }
+
+                BundleData::Enum {
+                    discriminant,
+                    variants,
+                    default,
                 }
             }
+            Data::Union(u) => {
+                return Err(syn::Error::new(
+                    u.union_token.span,
+                    "Only structs and enums are supported for Qeap Bundles",
+                ));
+            }
         };
 
-        tokens.extend(t);
+        Ok(Self {
+            type_name: c.ident.clone(),
+            generics: c.generics.clone(),
+            parallel: bundle_attrs.parallel,
+            data,
+        })
     }
-}
 
-#[proc_macro_derive(Bundle)]
-pub fn derive_qeap_bundle(input: TokenStream) -> TokenStream {
-    let c = parse_macro_input!(input as DeriveInput);
+    fn fields(&self) -> Box<dyn Iterator<Item = &BundleField> + '_> {
+        match &self.data {
+            BundleData::Struct(fields) => Box::new(fields.iter()),
+            BundleData::Enum { variants, .. } => {
+                Box::new(variants.iter().flat_map(|v| v.fields.iter()))
+            }
+        }
+    }
+
+    fn load_and_save(&self) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+        match &self.data {
+            BundleData::Struct(fields) => {
+                let load = load_fields(quote! { Self }, fields, self.parallel);
+                let values = fields
+                    .iter()
+                    .map(|f| {
+                        let member = &f.member;
+                        quote! { &self.#member }
+                    })
+                    .collect();
+                let saves = save_fields(fields, values, self.parallel);
+
+                let save = quote! {
+                    #saves
+                    Ok(())
+                };
+
+                (load, save)
+            }
+            BundleData::Enum {
+                discriminant,
+                variants,
+                default,
+            } => {
+                let type_name_str = self.type_name.to_string();
+                let name = persistence_name(&self.generics, &type_name_str);
+
+                let variant_names = variants.iter().map(|v| v.ident.to_string());
+                let loads = variants.iter().map(|v| v.load(self.parallel));
+                let fallback = match default {
+                    Some(ident) => {
+                        let load = variants
+                            .iter()
+                            .find(|v| v.ident == *ident)
+                            .expect("the default is one of the variants")
+                            .load(self.parallel);
+                        quote! { _ => #load }
+                    }
+                    None => quote! {
+                        other => Err(::qeap::error::Error::load(::qeap::error::SimpleErr(
+                            ::std::format!("unknown {} variant `{}`", #type_name_str, other),
+                        ))),
+                    },
+                };
+
+                let load = quote! {
+                    let p = #discriminant;
+                    ::qeap::Qeaper::init(&p)?;
+                    let variant: ::std::string::String = ::qeap::Qeaper::load(&p, #name)?;
+
+                    match variant.as_str() {
+                        #(#variant_names => #loads,)*
+                        #fallback
+                    }
+                };
 
-    let type_name = c.ident.clone();
+                let arms = variants.iter().map(|v| {
+                    let pattern = v.destructure();
+                    let variant_name = v.ident.to_string();
+                    let values = v
+                        .fields
+                        .iter()
+                        .map(|f| f.binding().into_token_stream())
+                        .collect();
+                    let saves = save_fields(&v.fields, values, self.parallel);
+
+                    quote! {
+                        #pattern => {
+                            ::qeap::Qeaper::save(
+                                &p,
+                                &::std::string::String::from(#variant_name),
+                                #name,
+                            )?;
+                            #saves
+                        }
+                    }
+                });
 
-    let out = if let Data::Struct(s) = c.data {
-        let tuple_struct = s.fields.iter().any(|f| f.ident.is_none());
+                let save = quote! {
+                    let p = #discriminant;
+                    ::qeap::Qeaper::init(&p)?;
+
+                    match self {
+                        #(#arms)*
+                    }
 
-        let bundle = if tuple_struct {
-            Bundle {
-                type_name,
-                strategy: BundleStrategy::TupleStruct(0..s.fields.len()),
+                    Ok(())
+                };
+
+                (load, save)
             }
-        } else {
-            Bundle {
-                type_name,
-                strategy: BundleStrategy::Struct(
-                    s.fields
-                        .into_iter()
-                        .map(|f| f.ident.expect("non tuple structs must have field names"))
-                        .collect(),
-                ),
+        }
+    }
+}
+
+impl ToTokens for Bundle {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let type_name = &self.type_name;
+
+        // every member has to be Qeap itself, which matters once they depend on type parameters
+        let mut generics = self.generics.clone();
+        let mut bounds = self
+            .fields()
+            .flat_map(|f| f.bounds(self.parallel))
+            .collect::<Vec<_>>();
+        // enums store their variant under a name built from the type arguments
+        if matches!(self.data, BundleData::Enum { .. }) {
+            bounds.extend(name_bounds(&self.generics));
+        }
+        if !bounds.is_empty() {
+            generics.make_where_clause().predicates.extend(bounds);
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        let (load, save) = self.load_and_save();
+
+        tokens.extend(quote! {
+            impl #impl_generics ::qeap::Qeap for #type_name #ty_generics #where_clause {
+                fn load() -> qeap::QeapResult<Self>
+                where
+                    Self: Sized
+                {
+                    #load
+                }
+
+                fn save(&self) -> qeap::QeapResult<()> {
+                    #save
+                }
             }
-        };
+        });
+    }
+}
 
-        quote! { #bundle }
-    } else {
-        panic!("Only structs are currently supported for Qeap Bundles");
+#[proc_macro_derive(Bundle, attributes(bundle))]
+pub fn derive_qeap_bundle(input: TokenStream) -> TokenStream {
+    let c = parse_macro_input!(input as DeriveInput);
+
+    let out = match Bundle::from_derive_input(&c) {
+        Ok(bundle) => bundle.into_token_stream(),
+        Err(e) => e.to_compile_error(),
     };
 
     out.into()
@@ -174,11 +819,13 @@ pub fn derive_qeap_bundle(input: TokenStream) -> TokenStream {
 
 #[proc_macro_attribute]
 pub fn scoped(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let scoped_mode = parse_macro_input!(attr as ScopedMode);
+    let scoped_args = parse_macro_input!(attr as ScopedArgs);
     let func = parse_macro_input!(item as syn::ItemFn);
-    let scoped_fn = create_scoped_fn(scoped_mode, func);
 
-    let out = quote! { #scoped_fn };
+    let out = match create_scoped_fn(scoped_args, func) {
+        Ok(scoped_fn) => quote! { #scoped_fn },
+        Err(e) => e.to_compile_error(),
+    };
 
     out.into()
 }
@@ -203,6 +850,7 @@ impl ToTokens for VarUse<'_> {
             VarType::Handle(ty) => {
                 quote! { <#ty as ::qeap::Handle>::new_handle(&#name) }
             }
+            VarType::PassThrough(_) => quote! { #name },
         };
 
         tokens.extend(as_tokens);
@@ -219,11 +867,11 @@ impl ToTokens for FieldDeclaration<'_> {
         let name = self.name;
         let ty = match &self.var_type {
             VarType::ImmutableRef(r) | VarType::MutableRef(r) => &*r.elem,
-            VarType::Handle(t) => t,
+            VarType::Handle(t) | VarType::PassThrough(t) => t,
         };
 
         let as_tokens = match self.var_type {
-            VarType::ImmutableRef(_) | VarType::Handle(_) => quote! {
+            VarType::ImmutableRef(_) | VarType::Handle(_) | VarType::PassThrough(_) => quote! {
                 let #name: #ty
             },
             VarType::MutableRef(_) => {
@@ -238,6 +886,7 @@ impl ToTokens for FieldDeclaration<'_> {
 struct ScopeField {
     name: Ident,
     var_type: VarType,
+    save: bool,
 }
 
 impl ScopeField {
@@ -254,10 +903,38 @@ impl ScopeField {
             var_type: &self.var_type,
         }
     }
+
+    fn is_persisted(&self) -> bool {
+        !matches!(self.var_type, VarType::PassThrough(_))
+    }
+
+    fn as_pass_through_param(&self) -> Option<FnArg> {
+        let name = &self.name;
+        match &self.var_type {
+            VarType::PassThrough(ty) => Some(parse_quote! { #name: #ty }),
+            _ => None,
+        }
+    }
+
+    /// Whether the parameter is an `Arc`, and so can be handed to a background saver.
+    ///
+    /// This only sees how the type is spelled, so aliases of `Arc` aren't recognized.
+    fn is_shared(&self) -> bool {
+        match &self.var_type {
+            VarType::Handle(Type::Path(type_path)) => type_path
+                .path
+                .segments
+                .last()
+                .is_some_and(|seg| seg.ident == "Arc"),
+            _ => false,
+        }
+    }
 }
 
-impl From<&PatType> for ScopeField {
-    fn from(value: &PatType) -> Self {
+impl TryFrom<&PatType> for ScopeField {
+    type Error = syn::Error;
+
+    fn try_from(value: &PatType) -> syn::Result<Self> {
         match &*value.pat {
             syn::Pat::Ident(field_name) => {
                 let name = field_name.ident.clone();
@@ -280,14 +957,24 @@ impl From<&PatType> for ScopeField {
                     other => VarType::Handle(other.clone()),
                 };
 
-                Self {
+                // data that is only borrowed immutably can't have changed, so there's nothing to save
+                let save = match &ref_type {
+                    VarType::ImmutableRef(_) => false,
+                    VarType::Handle(ty) => !is_shared_immutable(ty),
+                    _ => true,
+                };
+
+                Ok(Self {
                     name,
                     var_type: ref_type,
-                }
+                    save,
+                })
             }
-            other => panic!(
-                "Only ident pattern function arguments are supported, i.e. `field: Type`: {other:?}"
-            ),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "Only ident pattern function arguments are supported, i.e. `field: Type`. \
+                 Mark the parameter with `#[qeap(skip)]` if it shouldn't be persisted",
+            )),
         }
     }
 }
@@ -296,6 +983,55 @@ enum VarType {
     ImmutableRef(TypeReference),
     MutableRef(TypeReference),
     Handle(Type),
+    // not persisted, the caller passes it to the scoped function as usual
+    PassThrough(Type),
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+enum ParamKind {
+    // persisted, and saved unless it's an immutable reference
+    #[default]
+    Inferred,
+    Skip,
+    Save,
+    Readonly,
+}
+
+impl ParamKind {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut kind = Self::default();
+
+        for attr in attrs {
+            if !attr.path().is_ident("qeap") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                let parsed = if meta.path.is_ident("skip") {
+                    Self::Skip
+                } else if meta.path.is_ident("save") {
+                    Self::Save
+                } else if meta.path.is_ident("readonly") {
+                    Self::Readonly
+                } else {
+                    return Err(meta.error(
+                        "unknown qeap parameter attribute, expected `skip`, `save` or `readonly`",
+                    ));
+                };
+
+                if kind != Self::Inferred {
+                    return Err(meta.error(
+                        "only one of `skip`, `save` or `readonly` can be used on a parameter",
+                    ));
+                }
+
+                kind = parsed;
+                Ok(())
+            })?;
+        }
+
+        Ok(kind)
+    }
 }
 
 #[derive(Default, Clone, Copy)]
@@ -303,27 +1039,24 @@ enum ScopedMode {
     #[default]
     Nested,
     Flatten,
+    FlattenErased,
     Absorb,
     Expect,
 }
 
-impl Parse for ScopedMode {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        if input.is_empty() {
-            return Ok(Self::default());
-        }
-
-        let ident: Ident = input.parse()?;
-
+impl ScopedMode {
+    fn from_ident(ident: &Ident) -> syn::Result<Self> {
         let mode = match ident.to_string().to_lowercase().as_str() {
+            "nested" => Self::Nested,
             "flatten" => Self::Flatten,
+            "flatten_erased" => Self::FlattenErased,
             "absorb" => Self::Absorb,
             "expect" => Self::Expect,
             other => {
                 return Err(syn::Error::new(
                     ident.span(),
                     format!(
-                        "Expected 'flatten', 'flatten_erased', 'absorb', or 'expect', got '{other}'"
+                        "Expected 'nested', 'flatten', 'flatten_erased', 'absorb', or 'expect', got '{other}'"
                     ),
                 ));
             }
@@ -333,47 +1066,189 @@ impl Parse for ScopedMode {
     }
 }
 
-fn get_result_path_segment(ty: &Type) -> Option<&PathSegment> {
-    if let Type::Path(type_path) = ty {
-        let seg = type_path.path.segments.last()?;
+#[derive(Default)]
+struct ScopedArgs {
+    mode: ScopedMode,
+    autosave: Option<AutosaveInterval>,
+    // span of `save_on = "ok"`, if given
+    save_on_ok: Option<Span>,
+}
 
-        if seg.ident.to_string().contains("Result") {
-            Some(seg)
-        } else {
-            None
+impl Parse for ScopedArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        let mut mode_span = None;
+
+        for meta in Punctuated::<Meta, Token![,]>::parse_terminated(input)? {
+            match &meta {
+                Meta::Path(path) => {
+                    let ident = path.require_ident()?;
+                    if mode_span.is_some() {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            "only one scoped mode can be specified",
+                        ));
+                    }
+                    args.mode = ScopedMode::from_ident(ident)?;
+                    mode_span = Some(ident.span());
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("autosave") => {
+                    args.autosave = Some(AutosaveInterval::parse(&nv.value)?);
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("save_on") => {
+                    let save_on = match &nv.value {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Str(lit), ..
+                        }) => lit.value(),
+                        _ => String::new(),
+                    };
+
+                    args.save_on_ok = match save_on.as_str() {
+                        "ok" => Some(nv.value.span()),
+                        "always" => None,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                &nv.value,
+                                "save_on expects \"ok\" or \"always\"",
+                            ));
+                        }
+                    };
+                }
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "Expected a scoped mode, `autosave = \"<duration>\"` or `save_on = \"ok\"`",
+                    ));
+                }
+            }
         }
-    } else {
-        None
+
+        Ok(args)
     }
 }
 
-fn extract_result_ok_err_types(result_seg: &PathSegment) -> (&Type, &Type) {
-    if let PathArguments::AngleBracketed(args) = &result_seg.arguments {
-        let mut iter = args.args.iter();
+struct AutosaveInterval {
+    millis: u64,
+    span: Span,
+}
 
-        let ok_ty = match iter.next() {
-            Some(GenericArgument::Type(ok_ty)) => ok_ty,
-            _ => panic!(
-                "If a Result type is specified, both Ok and Err types (T and E) must be included in the signature"
-            ),
+impl AutosaveInterval {
+    fn parse(value: &Expr) -> syn::Result<Self> {
+        let Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) = value
+        else {
+            return Err(syn::Error::new_spanned(
+                value,
+                "autosave expects a duration string, e.g. \"30s\"",
+            ));
         };
 
-        let err_ty = match iter.next() {
-            Some(GenericArgument::Type(err_ty)) => err_ty,
-            _ => panic!(
-                "If a Result type is specified, both Ok and Err types (T and E) must be included in the signature"
-            ),
+        let invalid = || {
+            syn::Error::new(
+                lit.span(),
+                "autosave expects a non-zero duration with a unit of 'ms', 's', 'm' or 'h', e.g. \"30s\"",
+            )
         };
 
-        return (ok_ty, err_ty);
-    } else {
-        panic!(
-            "If a Result type is specified, both Ok and Err types (T and E) must be included in the signature"
-        );
+        let value = lit.value();
+        let unit_start = value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len());
+        let (amount, unit) = value.split_at(unit_start);
+        let amount: u64 = amount.parse().map_err(|_| invalid())?;
+
+        let unit_millis = match unit {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            _ => return Err(invalid()),
+        };
+
+        match amount.checked_mul(unit_millis) {
+            Some(millis) if millis > 0 => Ok(Self {
+                millis,
+                span: lit.span(),
+            }),
+            _ => Err(invalid()),
+        }
     }
 }
 
-fn gather_scoped_fields(func: &ItemFn) -> Vec<ScopeField> {
+fn get_result_path_segment(ty: &Type) -> Option<&PathSegment> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+
+    type_path
+        .path
+        .segments
+        .last()
+        .filter(|seg| seg.ident == "Result")
+}
+
+/// The last path segment of `ty` and its first generic type argument, e.g. `Arc` and `T` for
+/// `std::sync::Arc<T>`.
+fn single_type_arg(ty: &Type) -> Option<(&Ident, Option<&Type>)> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let seg = type_path.path.segments.last()?;
+
+    let arg = match &seg.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    };
+
+    Some((&seg.ident, arg))
+}
+
+/// Whether `ty` is an `Rc<T>` or `Arc<T>` whose data can't be changed through it, i.e. `T`
+/// isn't a `Mutex`, `RwLock` or `RefCell`.
+fn is_shared_immutable(ty: &Type) -> bool {
+    let Some((ident, Some(inner))) = single_type_arg(ty) else {
+        return false;
+    };
+    if ident != "Rc" && ident != "Arc" {
+        return false;
+    }
+
+    !single_type_arg(inner)
+        .is_some_and(|(inner, _)| inner == "Mutex" || inner == "RwLock" || inner == "RefCell")
+}
+
+fn extract_result_ok_err_types(result_seg: &PathSegment) -> syn::Result<(&Type, &Type)> {
+    let missing_types = || {
+        syn::Error::new_spanned(
+            result_seg,
+            "If a Result type is specified, both Ok and Err types (T and E) must be included in the signature",
+        )
+    };
+
+    let PathArguments::AngleBracketed(args) = &result_seg.arguments else {
+        return Err(missing_types());
+    };
+
+    let mut iter = args.args.iter();
+
+    let ok_ty = match iter.next() {
+        Some(GenericArgument::Type(ok_ty)) => ok_ty,
+        _ => return Err(missing_types()),
+    };
+
+    let err_ty = match iter.next() {
+        Some(GenericArgument::Type(err_ty)) => err_ty,
+        _ => return Err(missing_types()),
+    };
+
+    Ok((ok_ty, err_ty))
+}
+
+fn gather_scoped_fields(func: &ItemFn) -> syn::Result<Vec<ScopeField>> {
     func.sig
         .inputs
         .iter()
@@ -381,13 +1256,52 @@ fn gather_scoped_fields(func: &ItemFn) -> Vec<ScopeField> {
             syn::FnArg::Receiver(_) => None,
             syn::FnArg::Typed(pat_type) => Some(pat_type),
         })
-        .map(ScopeField::from)
+        .enumerate()
+        .map(|(i, pat_type)| {
+            let kind = ParamKind::parse(&pat_type.attrs)?;
+
+            if kind != ParamKind::Skip {
+                let mut field = ScopeField::try_from(pat_type)?;
+                match kind {
+                    ParamKind::Save => field.save = true,
+                    ParamKind::Readonly => field.save = false,
+                    ParamKind::Inferred | ParamKind::Skip => {}
+                }
+                return Ok(field);
+            }
+
+            // pass-through parameters may use any pattern, so they get a name of their own if needed
+            let name = match &*pat_type.pat {
+                syn::Pat::Ident(pat_ident) => pat_ident.ident.clone(),
+                _ => Ident::new(&format!("__qeap_arg_{i}"), Span::call_site()),
+            };
+
+            Ok(ScopeField {
+                name,
+                var_type: VarType::PassThrough((*pat_type.ty).clone()),
+                save: false,
+            })
+        })
         .collect()
 }
 
+fn strip_param_attributes(func: &mut ItemFn) {
+    for input in &mut func.sig.inputs {
+        if let syn::FnArg::Typed(pat_type) = input {
+            pat_type.attrs.retain(|attr| !attr.path().is_ident("qeap"));
+        }
+    }
+}
+
 struct ScopedFn {
     scoped_mode: ScopedMode,
+    autosave_millis: Option<u64>,
+    save_on_ok: bool,
     scoped_fields: Vec<ScopeField>,
+    receiver: Option<Receiver>,
+    /// Whether the function has to be an associated function, because it takes `self` or
+    /// mentions `Self`.
+    associated: bool,
     output: proc_macro2::TokenStream,
     og_func: ItemFn,
 }
@@ -395,36 +1309,160 @@ struct ScopedFn {
 impl ToTokens for ScopedFn {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let var_use = self.scoped_fields.iter().map(ScopeField::as_var_use);
-        let field_decls = self
-            .scoped_fields
-            .iter()
-            .map(ScopeField::as_field_declaration);
-        let field_names = self.scoped_fields.iter().map(|f| &f.name);
+        let persisted_fields = || self.scoped_fields.iter().filter(|f| f.is_persisted());
+        let field_decls = persisted_fields().map(ScopeField::as_field_declaration);
+        let saved_fields = || persisted_fields().filter(|f| f.save);
+        let field_names = saved_fields().map(|f| &f.name);
 
         let func_name = &self.og_func.sig.ident;
 
-        let inner_func_name = Ident::new(&format!("{}_inner", func_name), Span::call_site());
+        // nested functions can't take `self` or use `Self`, so associated functions get a hidden
+        // sibling function instead
+        let inner_func_name = match self.associated {
+            true => Ident::new(&format!("__qeap_{}_inner", func_name), Span::call_site()),
+            false => Ident::new(&format!("{}_inner", func_name), Span::call_site()),
+        };
 
         let mut func = self.og_func.clone();
 
         let return_expr = &self.output;
 
+        // the outer function takes over everything callers can see: attributes, docs,
+        // visibility, and the signature minus the persisted parameters
+        let mut attrs = std::mem::take(&mut func.attrs);
+        let vis = std::mem::replace(&mut func.vis, Visibility::Inherited);
+
+        // a sibling is configured and linted like the original, which holds the body, so
+        // lint expectations are only checked there
+        if self.associated {
+            func.attrs = attrs
+                .iter()
+                .filter(|attr| is_inner_fn_attribute(attr))
+                .cloned()
+                .collect();
+            for attr in &mut attrs {
+                if attr.path().is_ident("expect")
+                    && let Meta::List(list) = &mut attr.meta
+                {
+                    list.path = parse_quote! { allow };
+                }
+            }
+        }
+
+        let mut sig = self.og_func.sig.clone();
+        sig.inputs = self
+            .receiver
+            .iter()
+            .map(|receiver| {
+                let mut receiver = receiver.clone();
+                // `mut self` only matters to the inner method
+                if receiver.reference.is_none() {
+                    receiver.mutability = None;
+                }
+                FnArg::Receiver(receiver)
+            })
+            .chain(
+                self.scoped_fields
+                    .iter()
+                    .filter_map(ScopeField::as_pass_through_param),
+            )
+            .collect();
+        sig.output = parse_quote! { -> #return_expr };
+
         func.sig.ident = inner_func_name.clone();
 
+        // the inner function has its own copy of the generics, forward them in case they can't be inferred
+        let forwarded_generics = self
+            .og_func
+            .sig
+            .generics
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                GenericParam::Type(ty) => Some(&ty.ident),
+                GenericParam::Const(c) => Some(&c.ident),
+                GenericParam::Lifetime(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let turbofish = if forwarded_generics.is_empty() {
+            quote! {}
+        } else {
+            quote! { ::<#(#forwarded_generics),*> }
+        };
+
+        let await_inner = self.og_func.sig.asyncness.map(|_| quote! { .await });
+        let receiver = self.receiver.as_ref().map(|_| quote! { self, });
+        let mut call_inner = match self.associated {
+            true => quote! {
+                Self::#inner_func_name #turbofish (#receiver #(#var_use),*) #await_inner
+            },
+            false => quote! { #inner_func_name #turbofish (#(#var_use),*) #await_inner },
+        };
+        if self.og_func.sig.unsafety.is_some() {
+            call_inner = quote! { unsafe { #call_inner } };
+        }
+
+        let (nested_inner, sibling_inner) = match self.associated {
+            true => (quote! {}, quote! { #[doc(hidden)] #func }),
+            false => (quote! { #func }, quote! {}),
+        };
+
+        let (autosave_start, autosave_stop) = match self.autosave_millis {
+            Some(millis) => {
+                let shared = saved_fields().filter(|f| f.is_shared()).map(|f| &f.name);
+                (
+                    quote! {
+                        let __qeap_autosave = [#(
+                            ::qeap::Autosave::spawn(
+                                ::std::sync::Arc::clone(&#shared),
+                                ::std::time::Duration::from_millis(#millis),
+                            )
+                        ),*];
+                    },
+                    quote! { ::std::mem::drop(__qeap_autosave); },
+                )
+            }
+            None => Default::default(),
+        };
+
+        let mut saves = match self.scoped_mode {
+            ScopedMode::Expect => {
+                let expect_save_msg =
+                    saved_fields().map(|f| format!("{} data should save successfully", f.name));
+                quote! {
+                    #(
+                        ::qeap::Qeap::save(&#field_names).expect(#expect_save_msg);
+                    )*
+                }
+            }
+            _ => quote! {
+                #(
+                    ::qeap::Qeap::save(&#field_names)?;
+                )*
+            },
+        };
+        if self.save_on_ok {
+            saves = quote! {
+                if ::std::result::Result::is_ok(&result) {
+                    #saves
+                }
+            };
+        }
+
         let t = match self.scoped_mode {
             ScopedMode::Nested => {
                 quote! {
-                    fn #func_name() -> #return_expr {
-                        #func
+                    #(#attrs)* #vis #sig {
+                        #nested_inner
                         #(
                             #field_decls = ::qeap::Qeap::load()?;
                             )*
 
-                            let result = #inner_func_name(#(#var_use),*);
+                            #autosave_start
+                            let result = #call_inner;
+                            #autosave_stop
 
-                        #(
-                            ::qeap::Qeap::save(&#field_names)?;
-                        )*
+                        #saves
 
                         Ok(result)
                     }
@@ -432,62 +1470,63 @@ impl ToTokens for ScopedFn {
             }
             ScopedMode::Absorb => {
                 quote! {
-                    fn #func_name() -> #return_expr {
-                        #func
+                    #(#attrs)* #vis #sig {
+                        #nested_inner
                         #(
                             #field_decls = ::qeap::Qeap::load()?;
                             )*
 
-                            let result = #inner_func_name(#(#var_use),*);
+                            #autosave_start
+                            let result = #call_inner;
+                            #autosave_stop
 
-                        #(
-                            ::qeap::Qeap::save(&#field_names)?;
-                        )*
+                        #saves
 
                         result
                     }
                 }
             }
-            ScopedMode::Flatten => {
+            ScopedMode::Flatten | ScopedMode::FlattenErased => {
+                let flatten = match self.scoped_mode {
+                    ScopedMode::FlattenErased => quote! {
+                        ::qeap::transform::IntoFlattenedErasedResult::into_flattened_erased
+                    },
+                    _ => quote! { ::qeap::transform::IntoFlattenedResult::into_flattened },
+                };
+
                 quote! {
-                    fn #func_name() -> #return_expr {
-                        #func
+                    #(#attrs)* #vis #sig {
+                        #nested_inner
                         #(
                             #field_decls = ::qeap::Qeap::load()?;
                         )*
 
-                        let result = ::qeap::transform::IntoFlattenedResult::into_flattened(#inner_func_name(#(#var_use),*));
+                        #autosave_start
+                        let result = #flatten(#call_inner);
+                        #autosave_stop
 
-                        #(
-                            ::qeap::Qeap::save(&#field_names)?;
-                        )*
+                        #saves
 
                         result
                     }
                 }
             }
             ScopedMode::Expect => {
-                let expect_save_msg = self
-                    .scoped_fields
-                    .iter()
-                    .map(|f| format!("{} data should save successfully", f.name));
-                let expect_load_msg = self
-                    .scoped_fields
-                    .iter()
-                    .map(|f| format!("{} data should load successfully", f.name));
+                let expect_load_msg =
+                    persisted_fields().map(|f| format!("{} data should load successfully", f.name));
 
                 quote! {
-                    fn #func_name() -> #return_expr {
-                        #func
+                    #(#attrs)* #vis #sig {
+                        #nested_inner
                         #(
                             #field_decls = ::qeap::Qeap::load().expect(#expect_load_msg);
                             )*
 
-                            let result = #inner_func_name(#(#var_use),*);
+                            #autosave_start
+                            let result = #call_inner;
+                            #autosave_stop
 
-                        #(
-                            ::qeap::Qeap::save(&#field_names).expect(#expect_save_msg);
-                        )*
+                        #saves
 
                         result
                     }
@@ -496,32 +1535,88 @@ impl ToTokens for ScopedFn {
         };
 
         tokens.extend(t);
+        tokens.extend(sibling_inner);
     }
 }
 
-fn create_scoped_fn(scoped_mode: ScopedMode, func: ItemFn) -> ScopedFn {
-    let scoped_fields = gather_scoped_fields(&func);
+fn create_scoped_fn(scoped_args: ScopedArgs, mut func: ItemFn) -> syn::Result<ScopedFn> {
+    let ScopedArgs {
+        mode: scoped_mode,
+        autosave,
+        save_on_ok,
+    } = scoped_args;
+
+    if let Some(span) = save_on_ok {
+        let returns_result = match &func.sig.output {
+            ReturnType::Type(_, ty) => get_result_path_segment(ty).is_some(),
+            ReturnType::Default => false,
+        };
 
-    let output = determine_scoped_fn_output(scoped_mode, &func.sig.output);
+        if !returns_result {
+            return Err(syn::Error::new(
+                span,
+                "save_on = \"ok\" requires the function to return a Result",
+            ));
+        }
+    }
 
-    ScopedFn {
+    let scoped_fields = gather_scoped_fields(&func)?;
+    strip_param_attributes(&mut func);
+
+    let receiver = func.sig.receiver().cloned();
+    let associated = receiver.is_some() || mentions_self(func.to_token_stream());
+
+    if let Some(autosave) = &autosave
+        && !scoped_fields.iter().any(|f| f.save && f.is_shared())
+    {
+        return Err(syn::Error::new(
+            autosave.span,
+            "autosave requires at least one saved `Arc<Mutex<T>>` or `Arc<RwLock<T>>` parameter",
+        ));
+    }
+
+    let output = determine_scoped_fn_output(scoped_mode, &func.sig.output)?;
+
+    Ok(ScopedFn {
         scoped_mode,
+        autosave_millis: autosave.map(|a| a.millis),
+        save_on_ok: save_on_ok.is_some(),
         output,
         scoped_fields,
+        receiver,
+        associated,
         og_func: func,
-    }
+    })
+}
+
+fn mentions_self(tokens: proc_macro2::TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => ident == "Self",
+        proc_macro2::TokenTree::Group(group) => mentions_self(group.stream()),
+        _ => false,
+    })
+}
+
+/// Attributes that configure or lint the body of a scoped function, which its hidden sibling
+/// needs as well.
+fn is_inner_fn_attribute(attr: &Attribute) -> bool {
+    [
+        "cfg", "cfg_attr", "allow", "warn", "deny", "forbid", "expect",
+    ]
+    .iter()
+    .any(|name| attr.path().is_ident(name))
 }
 
 fn determine_scoped_fn_output(
     scoped_mode: ScopedMode,
     original_return_type: &ReturnType,
-) -> proc_macro2::TokenStream {
+) -> syn::Result<proc_macro2::TokenStream> {
     let original_output = match original_return_type {
         ReturnType::Default => quote! { () },
         ReturnType::Type(_, ty) => quote! { #ty },
     };
 
-    match scoped_mode {
+    let output = match scoped_mode {
         ScopedMode::Nested => {
             quote! { ::std::result::Result<#original_output, ::qeap::error::Error> }
         }
@@ -532,7 +1627,7 @@ fn determine_scoped_fn_output(
             ReturnType::Type(_, ty) => {
                 let (ok_ty, err_ty) = match get_result_path_segment(ty) {
                     Some(seg) => {
-                        let (ok_ty, err_ty) = extract_result_ok_err_types(seg);
+                        let (ok_ty, err_ty) = extract_result_ok_err_types(seg)?;
                         (quote! {#ok_ty}, quote! {#err_ty})
                     }
                     None => (quote! { #ty }, quote! { ::std::convert::Infallible }),
@@ -542,6 +1637,23 @@ fn determine_scoped_fn_output(
                 }
             }
         },
+        ScopedMode::FlattenErased => {
+            let ok_ty = match original_return_type {
+                ReturnType::Default => quote! { () },
+                ReturnType::Type(_, ty) => match get_result_path_segment(ty) {
+                    Some(seg) => {
+                        let (ok_ty, _) = extract_result_ok_err_types(seg)?;
+                        quote! { #ok_ty }
+                    }
+                    None => quote! { #ty },
+                },
+            };
+            quote! {
+                ::std::result::Result<#ok_ty, ::qeap::error::FlattenErasedError>
+            }
+        }
         ScopedMode::Absorb | ScopedMode::Expect => quote! { #original_output },
-    }
+    };
+
+    Ok(output)
 }